@@ -26,6 +26,193 @@ impl Drop for MemoryLeakTracer {
     }
 }
 
+/// Subcommands for the configuration.
+#[derive(Subcommand)]
+pub enum ConfigSubcommand {
+
+    /// Set an option.
+    Set {
+        /// The key of an option.
+        /// E.g. `cc_flags`.
+        #[clap()]
+        key: String,
+
+        /// The value of an option.
+        /// E.g. `-std=c++17 -xc++ -O1 -Wall`
+        #[clap(allow_hyphen_values = true)]
+        value: String,
+
+        /// Set the key even if it's unknown or maintained by oi_helper.
+        #[clap(short, long)]
+        force: bool,
+    },
+
+    /// Print the value of an option.
+    Get {
+        /// The key of an option.
+        #[clap()]
+        key: String,
+    },
+
+    /// List all the options and where their values come from.
+    List,
+
+    /// Remove an option, so the value from the upper level is used.
+    Unset {
+        /// The key of an option.
+        #[clap()]
+        key: String,
+    },
+
+}
+
+/// Subcommands for the precompiled header.
+#[derive(Subcommand)]
+pub enum PchSubcommand {
+
+    /// Precompile `bits/stdc++.h` with the flags of a build profile.
+    Build {
+        /// The build profile. Defaults to `default_profile` in the configuration.
+        #[clap(short='p', long)]
+        profile: Option<String>,
+    },
+
+}
+
+/// Subcommands for the named templates.
+#[derive(Subcommand)]
+pub enum TemplateSubcommand {
+
+    /// List the templates, and where they come from.
+    List,
+
+    /// Print a template.
+    Show {
+        /// The name of the template.
+        #[clap()]
+        name: String,
+    },
+
+    /// Add a template file to the user's templates, so it can be used with `create -t NAME`.
+    Add {
+        /// The name of the template.
+        #[clap()]
+        name: String,
+
+        /// The template file.
+        #[clap()]
+        file: std::path::PathBuf,
+
+        /// Add the template to the workspace instead, so it can be shared with the workspace.
+        #[clap(short, long)]
+        local: bool,
+
+        /// Replace the existing template with the same name.
+        #[clap(short, long)]
+        force: bool,
+    },
+
+    /// Remove a template from the user's templates.
+    Rm {
+        /// The name of the template.
+        #[clap()]
+        name: String,
+
+        /// Remove the template from the workspace instead.
+        #[clap(short, long)]
+        local: bool,
+    },
+
+}
+
+/// Subcommands for the snippets.
+#[derive(Subcommand)]
+pub enum SnippetSubcommand {
+
+    /// Insert snippets and the ones they require into a C++ source.
+    Add {
+        /// The source file, the extension isn't neccessary.
+        #[clap()]
+        file: String,
+
+        /// The names of the snippets.
+        #[clap(required = true)]
+        snippets: Vec<String>,
+    },
+
+    /// List the snippets.
+    List,
+
+    /// Print a snippet.
+    Show {
+        /// The name of the snippet.
+        #[clap()]
+        name: String,
+    },
+
+}
+
+/// Subcommands for the contest directories.
+#[derive(Subcommand)]
+pub enum ContestSubcommand {
+
+    /// Create the directory `ID/P/P.cpp` and a sample group using file IO for every problem.
+    Init {
+        /// The contest ID, which is the name of the directory.
+        #[clap()]
+        id: String,
+
+        /// The names of the problems, e.g. `a,b,c,d`.
+        #[clap(long, required = true, use_value_delimiter = true)]
+        problems: Vec<String>,
+
+        /// The template of the sources, see `template list`.
+        #[clap(short='t', long, default_value="default")]
+        template: String,
+    },
+
+    /// Check every problem of a contest directory, and write the sources into `ID.zip`.
+    Pack {
+        /// The contest directory. Defaults to the current directory.
+        #[clap(parse(from_os_str), default_value=".")]
+        dir: std::path::PathBuf,
+
+        /// Allow `#pragma GCC optimize`, which is banned in NOI.
+        #[clap(long)]
+        allow_optimize: bool,
+    },
+
+}
+
+/// Subcommands for the problem metadata.
+#[derive(Subcommand)]
+pub enum ProblemSubcommand {
+
+    /// Set a field of the metadata, e.g. `problem set a status AC` or `problem set a tags dp,greedy`.
+    /// An empty value removes the field.
+    Set {
+        /// The name of the source file, the extension isn't neccessary.
+        #[clap()]
+        target: String,
+
+        /// The field: oj, problem_id, url, title, tags, difficulty, status, attempts or notes.
+        #[clap()]
+        key: String,
+
+        /// The value of the field.
+        #[clap(allow_hyphen_values = true)]
+        value: String,
+    },
+
+    /// Print the metadata of a problem.
+    Show {
+        /// The name of the source file, the extension isn't neccessary.
+        #[clap()]
+        target: String,
+    },
+
+}
+
 /// Subcommands for the sample.
 #[derive(Subcommand)]
 pub enum SamplesSubcommand {
@@ -46,13 +233,13 @@ pub enum SamplesSubcommand {
         #[clap()]
         name: String,
 
-        /// The timeout of the sample.
-        #[clap(long, value_parser, default_value_t = 1000)]
-        timeout: u32,
+        /// The timeout of the sample. Defaults to `sample_timeout` in the configuration.
+        #[clap(long, value_parser)]
+        timeout: Option<u32>,
 
-        /// The memory limit.
-        #[clap(long, value_parser, default_value_t = 256)]
-        memory_limit: u32,
+        /// The memory limit. Defaults to `sample_memory_limit` in the configuration.
+        #[clap(long, value_parser)]
+        memory_limit: Option<u32>,
         
         /// The points
         #[clap(long, value_parser, default_value_t = 10)]
@@ -73,6 +260,23 @@ pub enum SamplesSubcommand {
 
     },
 
+    /// Make the tests read `NAME.in` and write `NAME.out` instead of stdin and stdout, like CSP and NOI problems.
+    FileIo {
+
+        /// The name of the samples group.
+        #[clap()]
+        name: String,
+
+        /// The name of the files. Defaults to the name of the group.
+        #[clap()]
+        file_name: Option<String>,
+
+        /// Use stdin and stdout again.
+        #[clap(long, conflicts_with = "file-name")]
+        off: bool,
+
+    },
+
 }
 
 /// Subcommands
@@ -95,37 +299,23 @@ enum OIHelperCommands {
 
     /// Config current workspace.
     Config {
-        /// The key of an option.  
-        /// E.g. `cc_flags`.
-        #[clap()]
-        key: String,
-
-        /// The value of an option.
-        /// E.g. `-std=c++17 -xc++ -O1 -Wall`
-        #[clap()]
-        value: String,
+        #[clap(subcommand)]
+        subcommand: ConfigSubcommand,
     },
 
     /// Edit the global configuration, which will be used when initializing workspace or updating oi_ws.json.
     GlobalConfig {
-        /// The key of an option.
-        /// E.g. `cc_flags`
-        #[clap()]
-        key: String,
-
-        /// The value of an option.
-        /// E.g. `-std=c++17 -xc++ -O1 -Wall`
-        #[clap()]
-        value: String,
+        #[clap(subcommand)]
+        subcommand: ConfigSubcommand,
     },
 
-    /// Create a C++ source file in the workspace.
+    /// Create a source file in the workspace. The language is decided by the extension, e.g. `a.py`.
     Create {
-        /// The name of the source file, the extension isn't neccessary.
+        /// The name of the source file, the extension isn't neccessary for `cc_default_extension`.
         #[clap()]
         name: String,
 
-        /// The template name. Defaults to default.
+        /// The template name, see `template list`, or the path to a template file. Defaults to default.
         #[clap(short='t', long, default_value="default")]
         template: String,
 
@@ -140,6 +330,10 @@ enum OIHelperCommands {
         /// Determine if enable the debug kit.
         #[clap(short='d', long)]
         debug_kit: bool,
+
+        /// Set a template variable, e.g. `--var url=https://...`. Can be used multiple times.
+        #[clap(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
     },
 
     /// Run the program.
@@ -151,11 +345,99 @@ enum OIHelperCommands {
         /// Determine if debug kit is enabled (so the things in debug({}) will be executed. By default, they won't be executed unless you enable this option.)
         #[clap(short='d', long)]
         debug: bool,
+
+        /// The build profile, e.g. `release`, `debug` or `sanitize`. Defaults to `default_profile` in the configuration.
+        #[clap(short='p', long)]
+        profile: Option<String>,
     },
 
     /// Display the info of current workspace.
     Info,
 
+    /// Remove the build directory `target/` of the workspace.
+    Clean,
+
+    /// Write compile_commands.json for all the C and C++ sources in the workspace, for clangd and editors.
+    Compdb {
+        /// The build profile. Defaults to `default_profile` in the configuration.
+        #[clap(short='p', long)]
+        profile: Option<String>,
+    },
+
+    /// Manage the precompiled `bits/stdc++.h`.
+    Pch {
+        #[clap(subcommand)]
+        subcommand: PchSubcommand,
+    },
+
+    /// Manage the named templates in `~/.oi_helper/templates` and the workspace's `.oi_helper/templates`.
+    Template {
+        #[clap(subcommand)]
+        subcommand: TemplateSubcommand,
+    },
+
+    /// Merge a C++ source and the files it includes with `#include "..."` into `NAME.submit.cc` for submitting.
+    Bundle {
+        /// The name of the source file, the extension isn't neccessary.
+        #[clap()]
+        target: String,
+
+        /// Check if the bundle compiles.
+        #[clap(long)]
+        verify: bool,
+
+        /// The build profile used by `--verify`. Defaults to `default_profile` in the configuration.
+        #[clap(short='p', long)]
+        profile: Option<String>,
+    },
+
+    /// Check a C++ source for the mistakes which may get it disqualified in official contests like NOI and CSP,
+    /// and if it compiles with `-std=c++14 -O2`.
+    Check {
+        /// The name of the source file, the extension isn't neccessary.
+        #[clap()]
+        target: String,
+
+        /// The problem reads `NAME.in` and writes `NAME.out`. NAME defaults to the name of the source file.
+        #[clap(long, value_name = "NAME")]
+        file_io: Option<Option<String>>,
+
+        /// Allow `#pragma GCC optimize`, which is banned in NOI.
+        #[clap(long)]
+        allow_optimize: bool,
+    },
+
+    /// Set up and pack the contest directories of official contests like CSP and NOIP.
+    Contest {
+        #[clap(subcommand)]
+        subcommand: ContestSubcommand,
+    },
+
+    /// Insert code snippets, like segment trees, into sources. Snippets are stored in `~/.oi_helper/snippets`
+    /// and the workspace's `.oi_helper/snippets`.
+    Snippet {
+        #[clap(subcommand)]
+        subcommand: SnippetSubcommand,
+    },
+
+    /// Edit the metadata of a problem, saved as `problem_info.json` in its sample group.
+    Problem {
+        #[clap(subcommand)]
+        subcommand: ProblemSubcommand,
+    },
+
+    /// Show the statistics of the problems in the workspace: solved and attempted problems by OJ, difficulty and tag,
+    /// the AC rate by week, and the unsolved problems not tested recently.
+    Stats {
+        /// List the unsolved problems not tested in this many days.
+        #[clap(long, value_parser, default_value_t = 14)]
+        days: u64,
+
+        /// Also write the statistics into a JSON file.
+        #[clap(long, parse(from_os_str), value_name = "PATH")]
+        json: Option<std::path::PathBuf>,
+    },
+
     /// Update the workspace to the newest oi_helper version.
     Update,
 
@@ -174,6 +456,50 @@ enum OIHelperCommands {
         /// The path-to-samples-directory (without .smpd extension). If not specified, it will be the same as the target.
         #[clap(short='s', long)]
         samples_pack: Option<String>, 
+
+        /// The build profile, e.g. `release`, `debug` or `sanitize`. Defaults to `default_profile` in the configuration.
+        #[clap(short='p', long)]
+        profile: Option<String>,
+    },
+
+    /// Show the test history of a target, and the source changes which made passed samples fail.
+    History {
+        /// The name of the source file, the extension isn't neccessary.
+        #[clap()]
+        target: String,
+
+        /// How many tests are shown, from the latest.
+        #[clap(short='n', long, value_parser, default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Receive problems from the Competitive Companion browser extension.
+    Listen {
+        /// The port to listen on.
+        #[clap(long, value_parser, default_value_t = 10043)]
+        port: u16,
+    },
+
+    /// Download the statement of a problem as a Markdown file.
+    Statement {
+        /// The problem, e.g. `luogu:P1001`.
+        #[clap()]
+        problem: String,
+
+        /// The name of the Markdown file without extension. Defaults to the problem id.
+        #[clap(short='n', long)]
+        name: Option<String>,
+    },
+
+    /// Fetch every problem of a contest or problem list, creating a source file and a sample group for each of them.
+    Fetch {
+        /// The contest or problem list, e.g. `cf:1850`, `atc:abc300`, `luogu-training:100` or `luogu-contest:12345`.
+        #[clap()]
+        list: String,
+
+        /// How many problems are fetched at the same time.
+        #[clap(short='j', long, value_parser, default_value_t = 4)]
+        jobs: usize,
     },
 
 }
@@ -183,6 +509,14 @@ enum OIHelperCommands {
 #[clap(author, version, about, long_about = None)]
 pub struct OIHelperCli {
 
+    /// Only use the cached web pages instead of fetching them.
+    #[clap(long, global = true)]
+    offline: bool,
+
+    /// The path to the workspace, instead of searching for `oi_ws.json` from the current directory upwards.
+    #[clap(long, global = true, parse(from_os_str))]
+    workspace: Option<std::path::PathBuf>,
+
     /// The subcommand.
     #[clap(subcommand)]
     subcommand: OIHelperCommands,
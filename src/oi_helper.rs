@@ -2,11 +2,37 @@
 
 use std::{path::Path, fs};
 
-use crate::OIHelperCommands;
+use crate::{ContestSubcommand, OIHelperCommands, PchSubcommand};
 
-use self::{workspace::Workspace, samples::Samples};
+use crossterm::style::Stylize;
+use json::JsonValue;
+
+use self::{workspace::Workspace, config::WorkspaceConfig, samples::Samples, templates::Registry, utils::web::{self, WebOptions}};
 
 mod workspace;
+mod build;
+mod bundle;
+mod checker;
+mod contest;
+mod config;
+mod config_cli;
+mod directives;
+mod companion;
+mod languages;
+mod compdb;
+mod pch;
+mod problem;
+mod problem_cli;
+mod fetch;
+mod history;
+mod history_cli;
+mod statement;
+mod stats;
+mod template;
+mod templates;
+mod template_cli;
+mod snippets;
+mod snippet_cli;
 mod resource;
 mod samples;
 mod samples_cli;
@@ -32,80 +58,229 @@ impl OIHelper {
             let mut p = user_home.clone();
             p.push(".oi_helper");
             if !p.as_path().exists() {
-                fs::create_dir(&p).unwrap();
+                if let Err(err) = fs::create_dir(&p) {
+                    eprintln!("{} Cannot create {}: {}. Using the builtin defaults. ", "[WARNING]".bold().yellow(), p.display(), err);
+                }
+            } else if !p.is_dir() {
+                eprintln!("{} {} is not a directory. Using the builtin defaults. ", "[WARNING]".bold().yellow(), p.display());
             }
-            let ps = p.to_str();
-            self.global_config_path = Some(String::from(ps.unwrap()));
+            self.global_config_path = if p.is_dir() { Some(p.to_string_lossy().to_string()) } else { None };
         } else {
             self.global_config_path = None;
         }
+
+        // Configure the web fetcher.
+        let global = Workspace::load_global_config(&self.global_config_path);
+        let global = WorkspaceConfig::from_json(&global, &JsonValue::Null).unwrap_or_else(|errors| {
+            for err in errors {
+                eprintln!("{} Invalid global configuration: {}", "[WARNING]".bold().yellow(), err);
+            }
+            WorkspaceConfig::default()
+        });
+        web::configure(WebOptions {
+            timeout: global.net_timeout,
+            retries: global.net_retries,
+            proxy: global.net_proxy,
+            user_agent: global.net_user_agent,
+            cache_dir: self.global_config_path.as_ref().map(|p| Path::new(p).join("cache")),
+            cache_ttl: global.net_cache_ttl,
+            offline: self.args.offline,
+        });
     }
     
+    /// Open the workspace containing the current directory, or the one given with `--workspace`.
+    fn open_workspace(&self) -> Result<Workspace, Option<String>> {
+        Workspace::open(self.args.workspace.as_deref(), &self.global_config_path)
+    }
+
+    /// Like `open_workspace`, but for commands which also work outside workspaces, unless one is given with `--workspace`.
+    fn find_workspace(&self) -> Result<Option<Workspace>, Option<String>> {
+        let cwd = std::env::current_dir().unwrap_or_else(|_| Path::new(".").to_path_buf());
+        if self.args.workspace.is_some() || Workspace::locate(&cwd).is_some() {
+            Ok(Some(self.open_workspace()?))
+        } else {
+            Ok(None)
+        }
+    }
+
     pub fn run(&mut self) -> Result<(), Option<String>> {
         match &self.args.subcommand {
 
             OIHelperCommands::Init { path } => {
-                Workspace::create(&path, &self.global_config_path.clone())?;
+                Workspace::create(path, &self.global_config_path.clone())?;
             },
 
             OIHelperCommands::Checkver { path } => {
-                let mut cfg_path = path.clone();
-                cfg_path.push("oi_ws.json");
-                Workspace::from_file(&cfg_path.as_path(), &self.global_config_path.clone())?.check_version("./oi_ws.json")?;
+                let cfg_path = Workspace::locate(path).unwrap_or_else(|| path.join("oi_ws.json"));
+                Workspace::from_file(&cfg_path, &self.global_config_path.clone())?.check_version()?;
             },
 
-            OIHelperCommands::Config { key, value } => {
-                let mut workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
-                workspace.set_config(key, value);
-                workspace.save_config(Path::new("./oi_ws.json"))?;
+            OIHelperCommands::Config { subcommand } => {
+                let mut workspace = self.open_workspace()?;
+                config_cli::workspace_config(&mut workspace, subcommand)?;
             },
 
-            OIHelperCommands::Create { name , template , maxn , maxl, debug_kit } => {
-                let mut workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
-                workspace.check_version("./oi_ws.json")?;
-                workspace.create_cpp(name, template, maxn, maxl, *debug_kit)?;
+            OIHelperCommands::Create { name , template , maxn , maxl, debug_kit, vars } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                let mut parsed_vars = Vec::new();
+                for var in vars {
+                    match var.split_once('=') {
+                        Some((key, value)) => parsed_vars.push((key.trim().to_string(), value.to_string())),
+                        None => return Err(Some(format!("Invalid template variable `{var}`, expected KEY=VALUE. "))),
+                    }
+                }
+                workspace.create_source(name, template, maxn, maxl, *debug_kit, &parsed_vars)?;
             },
 
-            OIHelperCommands::Run { name, debug } => {
-                let mut workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
-                workspace.check_version("./oi_ws.json")?;
-                workspace.run_cpp(name, *debug)?;
+            OIHelperCommands::Run { name, debug, profile } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                workspace.run_source(name, *debug, profile.as_deref())?;
             },
 
             OIHelperCommands::Info => {
-                let workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
+                let workspace = self.open_workspace()?;
                 workspace.display_info();
             },
 
+            OIHelperCommands::Clean => {
+                let workspace = self.open_workspace()?;
+                build::clean(&workspace.target_dir())?;
+            },
+
+            OIHelperCommands::Compdb { profile } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                let count = workspace.write_compdb(profile.as_deref())?;
+                println!("Wrote compile_commands.json with {} source(s). ", count);
+            },
+
+            OIHelperCommands::Pch { subcommand } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                match subcommand {
+                    PchSubcommand::Build { profile } => workspace.build_pch(profile.as_deref())?,
+                }
+            },
+
+            OIHelperCommands::Template { subcommand } => {
+                let workspace = self.find_workspace()?;
+                let (registry, scheme) = match &workspace {
+                    Some(workspace) => (workspace.templates(), workspace.config().cc_template.clone()),
+                    None => {
+                        let global = Workspace::load_global_config(&self.global_config_path);
+                        let scheme = WorkspaceConfig::from_json(&global, &JsonValue::Null).map(|c| c.cc_template).unwrap_or_default();
+                        (Registry::new(&self.global_config_path, None, "templates"), scheme)
+                    }
+                };
+                template_cli::template(&registry, &scheme, subcommand)?;
+            },
+
+            OIHelperCommands::Bundle { target, verify, profile } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                let output = workspace.bundle(target, *verify, profile.as_deref())?;
+                println!("{}", format!("Wrote {}", output.display()).green());
+            },
+
+            OIHelperCommands::Check { target, file_io, allow_optimize } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                let stem = Path::new(target).file_stem().unwrap_or_default().to_string_lossy().to_string();
+                let options = checker::Options {
+                    file_io: file_io.clone().map(|name| name.unwrap_or(stem)),
+                    allow_optimize: *allow_optimize,
+                };
+                workspace.check(target, &options)?;
+            },
+
+            OIHelperCommands::Contest { subcommand } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                match subcommand {
+                    ContestSubcommand::Init { id, problems, template } => {
+                        contest::init(&workspace, id, problems, template)?;
+                    }
+                    ContestSubcommand::Pack { dir, allow_optimize } => {
+                        let archive = contest::pack(&workspace, dir, *allow_optimize)?;
+                        println!("{}", format!("Wrote {}", archive.display()).green());
+                    }
+                }
+            },
+
+            OIHelperCommands::Snippet { subcommand } => {
+                let workspace = self.find_workspace()?;
+                let root = workspace.as_ref().map(|workspace| workspace.root());
+                let registry = Registry::new(&self.global_config_path, root, "snippets");
+                snippet_cli::snippet(&registry, workspace.as_ref(), subcommand)?;
+            },
+
             OIHelperCommands::Update => {
-                let mut workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
+                let mut workspace = self.open_workspace()?;
                 workspace.update();
-                workspace.save_config(&Path::new("./oi_ws.json"))?;
+                workspace.save_config()?;
             },
 
-            OIHelperCommands::GlobalConfig { key, value } => {
-                let mut workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
-                workspace.set_g_config(key, value)?;
+            OIHelperCommands::GlobalConfig { subcommand } => {
+                config_cli::global_config(&self.global_config_path, subcommand)?;
             },
 
             OIHelperCommands::Samples { subcommand } => {
-                let mut workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
-                workspace.check_version("./oi_ws.json")?;
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
                 samples_cli::samples(&mut workspace, subcommand)?;
             },
 
-            OIHelperCommands::Test { target, samples_pack } => {
-                let mut workspace = Workspace::from_file(Path::new("./oi_ws.json"), &self.global_config_path.clone())?;
-                workspace.check_version("./oi_ws.json")?;
+            OIHelperCommands::Problem { subcommand } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                problem_cli::problem(subcommand)?;
+            },
+
+            OIHelperCommands::Stats { days, json } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                stats::stats(workspace.root(), *days, json.as_deref())?;
+            },
+
+            OIHelperCommands::Test { target, samples_pack, profile } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
                 let path_to_sampledir_str;
                 if let Some(pack) = samples_pack {
                     path_to_sampledir_str = format!("./{}.smpd", pack.to_owned());
                 } else {
-                    path_to_sampledir_str = format!("./{}.smpd", target.to_owned());
+                    // `a.py` uses the sample group of `a`.
+                    path_to_sampledir_str = format!("./{}.smpd", Samples::group_of(target));
                 }
                 let path_to_sampledir = Path::new(&path_to_sampledir_str);
                 let mut samples = Samples::from_file(path_to_sampledir.join("samples_info.json").to_str().unwrap())?;
-                workspace.test(target, &mut samples)?;
+                let results = workspace.test(target, &mut samples, profile.as_deref())?;
+
+                history::record_test(path_to_sampledir, target, Path::new(&workspace.source_name(target)), results)?;
+            }
+
+            OIHelperCommands::History { target, limit } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                history_cli::history(target, *limit)?;
+            }
+
+            OIHelperCommands::Listen { port } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                companion::listen(&workspace, *port)?;
+            }
+
+            OIHelperCommands::Statement { problem, name } => {
+                statement::save_statement(problem, name.as_deref())?;
+            }
+
+            OIHelperCommands::Fetch { list, jobs } => {
+                let mut workspace = self.open_workspace()?;
+                workspace.check_version()?;
+                fetch::fetch_list(&workspace, list, *jobs)?;
             }
         }
         Ok(())
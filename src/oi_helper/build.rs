@@ -0,0 +1,135 @@
+//! This file contains the build directory of workspaces and the cache of compiled programs.
+
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crossterm::style::Stylize;
+
+use super::utils::hash::hex_hash;
+
+/// Get the file of an `#include "..."` line.
+pub fn quoted_include(line: &str) -> Option<&str> {
+    let include = line.trim().strip_prefix('#')?.trim_start().strip_prefix("include")?.trim();
+    include.strip_prefix('"').and_then(|l| l.split_once('"')).map(|(name, _)| name)
+}
+
+/// Find the files included with `#include "..."` by the source, recursively.
+/// The includes are resolved relative to the including file, and the ones that don't exist are skipped.
+pub fn local_includes(source: &Path) -> Vec<PathBuf> {
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![source.to_path_buf()];
+    let mut result = Vec::new();
+    while let Some(file) = stack.pop() {
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        let dir = file.parent().unwrap_or_else(|| Path::new("."));
+        for line in content.lines() {
+            let name = match quoted_include(line) {
+                Some(name) => name,
+                None => continue,
+            };
+            let path = dir.join(name);
+            if path.is_file() && visited.insert(path.clone()) {
+                result.push(path.clone());
+                stack.push(path);
+            }
+        }
+    }
+    result
+}
+
+/// A compilation of a source file.
+pub struct Build {
+    /// The compiler and its arguments.
+    pub command: Vec<String>,
+    pub source: PathBuf,
+    /// The file produced by the compiler.
+    pub output: PathBuf,
+}
+
+impl Build {
+    /// Hash everything deciding the output: the compiler, the arguments, the source and its local includes.
+    fn hash(&self) -> Result<String, Option<String>> {
+        let mut data = Vec::new();
+        for arg in &self.command {
+            data.extend(arg.as_bytes());
+            data.push(0);
+        }
+        for file in std::iter::once(self.source.clone()).chain(local_includes(&self.source)) {
+            match fs::read(&file) {
+                Ok(content) => {
+                    data.push(0);
+                    data.extend(file.to_string_lossy().as_bytes());
+                    data.push(0);
+                    data.extend(content);
+                }
+                Err(err) => {
+                    return Err(Some(format!("Cannot read {}: {}", file.display(), err)));
+                }
+            }
+        }
+        Ok(hex_hash(&data))
+    }
+
+    /// The file storing the hash of the last successful build.
+    fn hash_file(&self) -> PathBuf {
+        let mut name = self.output.clone().into_os_string();
+        name.push(".hash");
+        PathBuf::from(name)
+    }
+
+    /// Compile the source unless the output is up to date.
+    pub fn run(&self) -> Result<(), Option<String>> {
+        let hash = self.hash()?;
+        if self.output.exists() && fs::read_to_string(self.hash_file()).ok().as_deref() == Some(hash.as_str()) {
+            println!("{}", "Up to date. ".bold().green());
+            return Ok(());
+        }
+
+        if let Some(dir) = self.output.parent() {
+            if let Err(err) = fs::create_dir_all(dir) {
+                return Err(Some(format!("Cannot create the build directory: {err}")));
+            }
+        }
+        // Remove the old hash first, so a failed build is never treated as up to date.
+        let _ = fs::remove_file(self.hash_file());
+        if crate::is_debug() {
+            println!("[DEBUG] {}", self.command.join(" "));
+        }
+        let (program, args) = match self.command.split_first() {
+            Some(command) => command,
+            None => return Err(Some("The compile command is empty. ".to_string())),
+        };
+        match Command::new(program).args(args).status() {
+            Ok(status) if status.success() => {
+                if let Err(err) = fs::write(self.hash_file(), hash) {
+                    eprintln!("{} Cannot save the build cache: {}", "[WARNING]".bold().yellow(), err);
+                }
+                println!("{}", "Compiled. ".bold().green());
+                Ok(())
+            }
+            _ => Err(Some("Failed to compile the program. Stopped. (CE(0))".to_string())),
+        }
+    }
+}
+
+/// Remove the build directory.
+pub fn clean(target_dir: &Path) -> Result<(), Option<String>> {
+    if !target_dir.exists() {
+        println!("Nothing to clean. ");
+        return Ok(());
+    }
+    match fs::remove_dir_all(target_dir) {
+        Ok(_) => {
+            println!("{}", format!("Removed {}", target_dir.display()).green());
+            Ok(())
+        }
+        Err(err) => Err(Some(format!("Cannot remove {}: {}", target_dir.display(), err))),
+    }
+}
@@ -0,0 +1,102 @@
+//! This file contains the bundler, which merges a source and its local includes into one file for submitting.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{build::quoted_include, resource};
+
+/// What the debug kit is replaced with, so `debug_do(...)` still compiles and does nothing.
+const DEBUG_KIT_REPLACEMENT: &str = "#define debug_do(__b)";
+
+/// Inline the local includes of a source recursively.
+struct Bundler {
+    /// The files with `#pragma once` which have been inlined.
+    once: Vec<PathBuf>,
+    /// The files being inlined, for finding includes of themselves.
+    stack: Vec<PathBuf>,
+}
+
+impl Bundler {
+    /// Check if the file has `#pragma once` and has been inlined.
+    fn is_inlined(&self, file: &Path) -> bool {
+        self.once.contains(&fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf()))
+    }
+
+    fn inline(&mut self, file: &Path, result: &mut String) -> Result<(), String> {
+        let key = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+        if self.stack.contains(&key) {
+            return Err(format!("{} includes itself without `#pragma once`. ", file.display()));
+        }
+        let content = fs::read_to_string(file).map_err(|err| format!("Cannot read {}: {}", file.display(), err))?;
+        self.stack.push(key.clone());
+        let dir = file.parent().unwrap_or_else(|| Path::new("."));
+        for line in content.lines() {
+            if line.trim() == "#pragma once" {
+                self.once.push(key.clone());
+                continue;
+            }
+            match quoted_include(line) {
+                // Keep the includes which don't exist, they may be found by the compiler.
+                Some(name) if dir.join(name).is_file() => {
+                    if self.is_inlined(&dir.join(name)) {
+                        continue;
+                    }
+                    result.push_str(&format!("// begin of {}\n", name));
+                    self.inline(&dir.join(name), result)?;
+                    result.push_str(&format!("// end of {}\n", name));
+                }
+                _ => {
+                    result.push_str(line);
+                    result.push('\n');
+                }
+            }
+        }
+        self.stack.pop();
+        Ok(())
+    }
+}
+
+/// Replace the debug kit block with a definition doing nothing.
+/// The block is found by the first and the last lines of [`resource::CPP_TEMPLATE_DEBUG_KIT`].
+fn strip_debug_kit(source: &str) -> String {
+    let kit = resource::CPP_TEMPLATE_DEBUG_KIT.trim();
+    let start = kit.lines().next().unwrap_or_default();
+    let end = kit.lines().last().unwrap_or_default();
+    let mut result = String::new();
+    let mut in_kit = false;
+    for line in source.lines() {
+        match line.trim() {
+            line if line == start => {
+                in_kit = true;
+                result.push_str(DEBUG_KIT_REPLACEMENT);
+                result.push('\n');
+            }
+            line if in_kit && line == end => in_kit = false,
+            _ if in_kit => {}
+            _ => {
+                result.push_str(line);
+                result.push('\n');
+            }
+        }
+    }
+    result
+}
+
+/// Merge the source and its local includes into one file.
+pub fn bundle(source: &Path) -> Result<String, String> {
+    let mut bundler = Bundler { once: vec![], stack: vec![] };
+    let mut result = String::new();
+    bundler.inline(source, &mut result)?;
+    Ok(strip_debug_kit(&result))
+}
+
+/// Get the path of the bundle of a source, e.g. `a.submit.cc` for `a.cc`.
+pub fn output_path(source: &Path) -> PathBuf {
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    match source.extension() {
+        Some(extension) => source.with_file_name(format!("{}.submit.{}", stem, extension.to_string_lossy())),
+        None => source.with_file_name(format!("{}.submit", stem)),
+    }
+}
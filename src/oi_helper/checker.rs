@@ -0,0 +1,361 @@
+//! This file contains the pre-flight checks of sources before submitting them in official contests like NOI and CSP.
+
+use std::fmt;
+
+use crossterm::style::Stylize;
+
+/// The flags used by the judges of official contests.
+pub const OFFICIAL_FLAGS: &[&str] = &["-std=c++14", "-O2"];
+
+/// The headers which don't exist on the judges.
+const BANNED_HEADERS: &[&str] = &["windows.h", "conio.h"];
+
+/// How serious a finding is.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Severity {
+    /// Likely to get the submission disqualified or scored zero.
+    Error,
+    /// Worth a look, but may be fine.
+    Warning,
+}
+
+/// A problem found in the source.
+pub struct Finding {
+    pub severity: Severity,
+    /// The line number, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self.severity {
+            Severity::Error => "[ERROR]".bold().red(),
+            Severity::Warning => "[WARNING]".bold().yellow(),
+        };
+        write!(f, "{} line {}: {}", tag, self.line, self.message)
+    }
+}
+
+/// What is expected from the source.
+pub struct Options {
+    /// The problem's name if it reads `NAME.in` and writes `NAME.out`, `None` if it uses stdin and stdout.
+    pub file_io: Option<String>,
+    /// Allow `#pragma GCC optimize`, which is banned in NOI.
+    pub allow_optimize: bool,
+}
+
+/// Replace the comments with spaces, keeping the strings and the line breaks.
+fn strip_comments(source: &str) -> String {
+    let mut result = String::new();
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                while let Some(&c) = chars.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    chars.next();
+                    result.push(' ');
+                }
+                result.push(' ');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                result.push_str("  ");
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    result.push(if c == '\n' { '\n' } else { ' ' });
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '"' | '\'' => {
+                result.push(c);
+                let mut escaped = false;
+                for s in chars.by_ref() {
+                    result.push(s);
+                    if s == '\n' || (s == c && !escaped) {
+                        break;
+                    }
+                    escaped = s == '\\' && !escaped;
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// Get the string literals in a piece of code.
+fn string_literals(code: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut rest = code;
+    while let Some(start) = rest.find('"') {
+        let mut literal = String::new();
+        let mut escaped = false;
+        let mut end = None;
+        for (i, c) in rest[start + 1..].char_indices() {
+            if c == '"' && !escaped {
+                end = Some(start + 1 + i);
+                break;
+            }
+            escaped = c == '\\' && !escaped;
+            literal.push(c);
+        }
+        match end {
+            Some(end) => {
+                result.push(literal);
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    result
+}
+
+/// Check if `word` appears in the code as a whole identifier, followed by `(`.
+fn calls(code: &str, word: &str) -> bool {
+    code.match_indices(word).any(|(i, _)| {
+        let before = code[..i].chars().next_back();
+        let after = code[i + word.len()..].trim_start().chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric() || c == '_') && after == Some('(')
+    })
+}
+
+/// Get the file name opened by `freopen` in the line, if the first argument is a string literal.
+fn freopen_file(line: &str) -> Option<String> {
+    let start = line.find("freopen")? + "freopen".len();
+    let argument = line[start..].trim_start().strip_prefix('(')?.trim_start();
+    if argument.starts_with('"') {
+        string_literals(argument).into_iter().next()
+    } else {
+        None
+    }
+}
+
+/// Check the `freopen` calls against the expected file names.
+fn check_freopen(lines: &[(usize, &str, usize)], options: &Options, findings: &mut Vec<Finding>) {
+    let mut redirected = [false, false];
+    for (number, line, depth) in lines {
+        if !calls(line, "freopen") {
+            continue;
+        }
+        let stream = if line.contains("stdin") {
+            0
+        } else if line.contains("stdout") {
+            1
+        } else {
+            findings.push(Finding {
+                severity: Severity::Warning,
+                line: *number,
+                message: String::from("`freopen` doesn't redirect stdin or stdout. "),
+            });
+            continue;
+        };
+        let conditional = if *depth > 0 { "It's in a preprocessor condition, make sure the judge takes the right branch. " } else { "" };
+        let severity = if *depth > 0 { Severity::Warning } else { Severity::Error };
+        let file = freopen_file(line);
+        match (&options.file_io, file) {
+            (None, _) => findings.push(Finding {
+                severity,
+                line: *number,
+                message: format!("`freopen` is left, but the problem uses standard IO. {conditional}"),
+            }),
+            (Some(_), None) => {
+                findings.push(Finding {
+                    severity: Severity::Warning,
+                    line: *number,
+                    message: String::from("Cannot check the file name of `freopen`, which isn't a string literal. "),
+                });
+                redirected[stream] = true;
+            }
+            (Some(name), Some(file)) => {
+                let expected = format!("{}.{}", name, if stream == 0 { "in" } else { "out" });
+                if file != expected {
+                    findings.push(Finding {
+                        severity,
+                        line: *number,
+                        message: format!("`freopen` opens \"{file}\", but \"{expected}\" is expected. {conditional}"),
+                    });
+                } else if *depth > 0 {
+                    findings.push(Finding {
+                        severity: Severity::Warning,
+                        line: *number,
+                        message: format!("`freopen` of \"{file}\" is in a preprocessor condition, make sure the judge takes the right branch. "),
+                    });
+                }
+                redirected[stream] = true;
+            }
+        }
+    }
+    if let Some(name) = &options.file_io {
+        for (stream, extension, redirected) in [("stdin", "in", redirected[0]), ("stdout", "out", redirected[1])] {
+            if !redirected {
+                findings.push(Finding {
+                    severity: Severity::Error,
+                    line: 1,
+                    message: format!("The problem uses file IO, but {stream} isn't redirected to \"{name}.{extension}\" with `freopen`. "),
+                });
+            }
+        }
+    }
+}
+
+/// Check if a string printed to stdout looks like debug output, e.g. `"x = "` or `"debug"`.
+fn looks_like_debug(literal: &str) -> bool {
+    let trimmed = literal.trim_end_matches("\\n").trim_end();
+    literal.to_lowercase().contains("debug") || trimmed.ends_with('=') || trimmed.ends_with(':')
+}
+
+/// Scan the source for the mistakes which may get it disqualified.
+pub fn scan(source: &str, options: &Options) -> Vec<Finding> {
+    let code = strip_comments(source);
+    let mut findings = Vec::new();
+
+    // Find the lines and how deep they are in `#if` blocks.
+    let mut lines = Vec::new();
+    let mut depth = 0_usize;
+    for (i, line) in code.lines().enumerate() {
+        let directive = line.trim().strip_prefix('#').map(str::trim_start).unwrap_or_default();
+        if directive.starts_with("endif") {
+            depth = depth.saturating_sub(1);
+        }
+        lines.push((i + 1, line, depth));
+        if directive.starts_with("if") {
+            depth += 1;
+        }
+    }
+
+    check_freopen(&lines, options, &mut findings);
+
+    for (number, line, depth) in &lines {
+        let number = *number;
+        let directive = line.trim().strip_prefix('#').map(str::trim_start).unwrap_or_default();
+
+        if let Some(header) = directive.strip_prefix("include") {
+            let header = header.trim().trim_matches(|c| c == '<' || c == '>' || c == '"').to_lowercase();
+            if BANNED_HEADERS.contains(&header.as_str()) {
+                findings.push(Finding {
+                    severity: Severity::Error,
+                    line: number,
+                    message: format!("<{header}> doesn't exist on the judges, which run Linux. "),
+                });
+            }
+        }
+
+        let pragma = directive.strip_prefix("pragma").map(|p| p.split_whitespace().collect::<Vec<&str>>().join(" "));
+        let optimize = pragma.is_some_and(|p| p.starts_with("GCC optimize") || p.starts_with("GCC target"))
+            || line.contains("__attribute__((optimize") || line.contains("__attribute__((__optimize__");
+        if optimize && !options.allow_optimize {
+            findings.push(Finding {
+                severity: Severity::Error,
+                line: number,
+                message: String::from("Optimizing pragmas and attributes are banned. "),
+            });
+        }
+
+        if calls(line, "system") {
+            findings.push(Finding {
+                severity: Severity::Error,
+                line: number,
+                message: String::from("`system()` is banned. "),
+            });
+        }
+
+        // Output in `#if` blocks, like the debug kit, isn't compiled by the judges.
+        let prints = calls(line, "printf") || calls(line, "puts") || line.contains("cout");
+        if prints && *depth == 0 && !line.contains("debug_do") {
+            if let Some(literal) = string_literals(line).into_iter().find(|l| looks_like_debug(l)) {
+                findings.push(Finding {
+                    severity: Severity::Warning,
+                    line: number,
+                    message: format!("\"{literal}\" looks like debug output on stdout. Use stderr or `debug_do` instead. "),
+                });
+            }
+        }
+    }
+
+    findings.sort_by_key(|finding| finding.line);
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_with(source: &str, file_io: Option<&str>, allow_optimize: bool) -> Vec<(Severity, usize)> {
+        let options = Options { file_io: file_io.map(String::from), allow_optimize };
+        scan(source, &options).into_iter().map(|finding| (finding.severity, finding.line)).collect()
+    }
+
+    fn scan_stdio(source: &str) -> Vec<(Severity, usize)> {
+        scan_with(source, None, false)
+    }
+
+    #[test]
+    fn clean_source() {
+        let source = "#include <cstdio>\nint main() {\n    int n;\n    scanf(\"%d\", &n);\n    printf(\"%d\\n\", n);\n}\n";
+        assert!(scan_stdio(source).is_empty());
+        assert!(scan_with("// freopen(\"a.in\", \"r\", stdin);\n/* system(\"pause\"); */\n", None, false).is_empty());
+    }
+
+    #[test]
+    fn freopen_names() {
+        let both = "int main() {\n    freopen(\"game.in\", \"r\", stdin);\n    freopen(\"game.out\", \"w\", stdout);\n}\n";
+        assert!(scan_with(both, Some("game"), false).is_empty());
+        assert_eq!(scan_with(both, Some("play"), false), vec![(Severity::Error, 2), (Severity::Error, 3)]);
+        assert_eq!(scan_stdio(both), vec![(Severity::Error, 2), (Severity::Error, 3)]);
+
+        // Each stream that isn't redirected is reported.
+        let input_only = "int main() {\n    freopen(\"game.in\", \"r\", stdin);\n}\n";
+        assert_eq!(scan_with(input_only, Some("game"), false), vec![(Severity::Error, 1)]);
+        assert_eq!(scan_with("int main() {}\n", Some("game"), false), vec![(Severity::Error, 1), (Severity::Error, 1)]);
+
+        // A name which isn't a literal cannot be checked.
+        let variable = "int main() {\n    freopen(name, \"r\", stdin);\n    freopen(\"game.out\", \"w\", stdout);\n}\n";
+        assert_eq!(scan_with(variable, Some("game"), false), vec![(Severity::Warning, 2)]);
+    }
+
+    #[test]
+    fn freopen_in_preprocessor_conditions() {
+        let source = "int main() {\n#ifndef ONLINE_JUDGE\n    freopen(\"game.in\", \"r\", stdin);\n#endif\n}\n";
+        assert_eq!(scan_stdio(source), vec![(Severity::Warning, 3)]);
+    }
+
+    #[test]
+    fn banned_calls_and_headers() {
+        assert_eq!(scan_stdio("#include <windows.h>\n#include<conio.h>\n#include \"Windows.h\"\n").len(), 3);
+        assert_eq!(scan_stdio("int main() {\n    system(\"pause\");\n}\n"), vec![(Severity::Error, 2)]);
+        assert!(scan_stdio("int main() {\n    int system_count = 0;\n    my_system(1);\n}\n").is_empty());
+    }
+
+    #[test]
+    fn optimize_pragmas() {
+        let source = "#pragma GCC optimize(\"O3\")\n#pragma  GCC   target(\"avx2\")\nvoid f() __attribute__((optimize(\"O3\")));\n#pragma once\n";
+        assert_eq!(scan_stdio(source), vec![(Severity::Error, 1), (Severity::Error, 2), (Severity::Error, 3)]);
+        assert!(scan_with(source, None, true).is_empty());
+    }
+
+    #[test]
+    fn debug_output() {
+        let source = "int main() {\n    cout << \"x = \" << x;\n    cout << \"debug \" << x;\n    puts(\"ans:\");\n    printf(\"%d\\n\", x);\n}\n";
+        assert_eq!(scan_stdio(source), vec![(Severity::Warning, 2), (Severity::Warning, 3), (Severity::Warning, 4)]);
+    }
+
+    #[test]
+    fn debug_output_skipped() {
+        let source = "int main() {\n#ifdef __DEBUG__\n    printf(\"x = %d\\n\", x);\n#endif\n    debug_do(printf(\"x = %d\\n\", x));\n    fprintf(stderr, \"x = %d\\n\", x);\n}\n";
+        assert!(scan_stdio(source).is_empty());
+    }
+
+    #[test]
+    fn comments_and_strings() {
+        assert_eq!(strip_comments("a // b\nc /* d\ne */ f"), "a     \nc     \n     f");
+        assert_eq!(strip_comments("\"// not a comment\" '/'"), "\"// not a comment\" '/'");
+        assert_eq!(string_literals(r#"printf("a\"b", "c");"#), vec![r#"a\"b"#, "c"]);
+    }
+}
@@ -0,0 +1,249 @@
+//! This file contains a tiny HTTP server that receives problems from the Competitive Companion browser extension.
+
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+use crossterm::style::Stylize;
+use json::{object, JsonValue};
+
+use super::{problem::ProblemInfo, samples::Samples, workspace::Workspace};
+
+/// The largest request body accepted. The problems are far smaller than this.
+const MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
+/// The largest size of the request line and the headers.
+const MAX_HEADER_SIZE: usize = 64 * 1024;
+/// How long to wait for a client sending the request, so a stalled one doesn't block the others.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A problem sent by Competitive Companion.
+pub struct CompanionProblem {
+    pub name: String,
+    /// The contest or the online judge, like `Codeforces - Codeforces Round 900`.
+    pub group: String,
+    pub url: String,
+    pub time_limit: u32,
+    pub memory_limit: u32,
+    pub tests: Vec<(String, String)>,
+}
+
+impl CompanionProblem {
+    /// Parse the JSON body posted by the extension.
+    pub fn from_json(body: &str) -> Result<Self, Option<String>> {
+        let obj = match json::parse(body) {
+            Ok(obj) => obj,
+            Err(err) => {
+                return Err(Some(format!("Received an invalid problem: {err}")));
+            }
+        };
+        let name = match obj["name"].as_str() {
+            Some(name) => name.to_string(),
+            None => {
+                return Err(Some("Received an invalid problem: missing name. ".to_string()));
+            }
+        };
+        let mut tests = Vec::new();
+        for test in obj["tests"].members() {
+            tests.push((
+                test["input"].as_str().unwrap_or("").to_string(),
+                test["output"].as_str().unwrap_or("").to_string(),
+            ));
+        }
+        Ok(Self {
+            name,
+            group: obj["group"].as_str().unwrap_or("").to_string(),
+            url: obj["url"].as_str().unwrap_or("").to_string(),
+            time_limit: obj["timeLimit"].as_u32().unwrap_or(1000),
+            memory_limit: obj["memoryLimit"].as_u32().unwrap_or(256),
+            tests,
+        })
+    }
+
+    /// The metadata of the problem, which will be saved into `problem_info.json`.
+    /// The online judge is the first part of the group, e.g. `codeforces` for `Codeforces - Codeforces Round 900`.
+    pub fn metadata(&self) -> JsonValue {
+        let oj = self.group.split(" - ").next().unwrap_or_default().trim().to_lowercase();
+        object! {
+            "oj": if oj.is_empty() { JsonValue::Null } else { oj.into() },
+            "url": self.url.clone(),
+            "title": self.name.clone(),
+            "time_limit": self.time_limit,
+            "memory_limit": self.memory_limit,
+        }
+    }
+
+    /// Generate a file name from the problem name, e.g. `A. Watermelon` becomes `A_Watermelon`.
+    pub fn file_name(&self) -> String {
+        let mut result = String::new();
+        for c in self.name.chars() {
+            if c.is_alphanumeric() || c == '-' {
+                result.push(c);
+            } else if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+        }
+        let result = result.trim_end_matches('_');
+        if result.is_empty() {
+            String::from("problem")
+        } else {
+            result.to_string()
+        }
+    }
+}
+
+/// A request which cannot be handled, with the status to answer.
+struct BadRequest {
+    /// The status line, e.g. `413 Payload Too Large`.
+    status: &'static str,
+    message: String,
+}
+
+impl BadRequest {
+    fn new(status: &'static str, message: String) -> Self {
+        Self { status, message }
+    }
+
+    /// An error while reading the request. The client is too slow if the read timed out.
+    fn read_error(err: std::io::Error) -> Self {
+        let status = match err.kind() {
+            ErrorKind::WouldBlock | ErrorKind::TimedOut => "408 Request Timeout",
+            _ => "400 Bad Request",
+        };
+        Self::new(status, format!("Error reading request: {err}"))
+    }
+}
+
+/// Read a whole HTTP request from the stream and return its body. Only `POST` requests are accepted.
+fn read_request_body(stream: &TcpStream) -> Result<String, BadRequest> {
+    if let Err(err) = stream.set_read_timeout(Some(READ_TIMEOUT)) {
+        return Err(BadRequest::new("500 Internal Server Error", format!("Cannot set the read timeout: {err}")));
+    }
+    let mut reader = BufReader::new(stream.take((MAX_HEADER_SIZE + MAX_BODY_SIZE) as u64));
+    let mut content_length = 0_usize;
+    let mut request_line = true;
+
+    // Read the request line and the headers.
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(err) => return Err(BadRequest::read_error(err)),
+        }
+        let line = line.trim_end();
+        if request_line {
+            request_line = false;
+            let method = line.split_whitespace().next().unwrap_or_default();
+            if method != "POST" {
+                return Err(BadRequest::new("405 Method Not Allowed", format!("Ignored a {method} request. ")));
+            }
+            continue;
+        }
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            if key.trim().eq_ignore_ascii_case("content-length") {
+                content_length = match value.trim().parse() {
+                    Ok(length) => length,
+                    Err(_) => {
+                        return Err(BadRequest::new("400 Bad Request", format!("Invalid Content-Length `{}`. ", value.trim())));
+                    }
+                };
+            }
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err(BadRequest::new(
+            "413 Payload Too Large",
+            format!("The request has {content_length} bytes, more than the limit of {MAX_BODY_SIZE} bytes. "),
+        ));
+    }
+
+    // Then read the body.
+    let mut body = vec![0_u8; content_length];
+    if let Err(err) = reader.read_exact(&mut body) {
+        return Err(BadRequest::read_error(err));
+    }
+    Ok(String::from_utf8_lossy(&body).to_string())
+}
+
+/// Create the source file and the sample group for a received problem.
+fn receive(workspace: &Workspace, problem: &CompanionProblem) -> Result<(), Option<String>> {
+    let name = problem.file_name();
+    eprintln!("Received problem {} ({}). ", problem.name.clone().bold(), problem.url);
+
+    // Create the source file, but never override the existing one.
+    if workspace.source_exists(&name) {
+        eprintln!("{} Source file of {} already exists, skipped. ", "[WARNING]".bold().yellow(), name);
+    } else {
+        let vars = [
+            (String::from("url"), problem.url.clone()),
+            (String::from("title"), problem.name.clone()),
+        ];
+        workspace.create_source(&name, "default", "1e5+114514", "128", false, &vars)?;
+    }
+
+    // Create the sample group, and save the metadata into it.
+    let mut samples = Samples::open_group(&name)?;
+    let mut info = ProblemInfo::of_group(&name)?;
+    info.merge(&problem.metadata());
+    info.save()?;
+    if !samples.is_empty() {
+        eprintln!("{} Sample group of {} is not empty, skipped. ", "[WARNING]".bold().yellow(), name);
+        return Ok(());
+    }
+    if problem.tests.is_empty() {
+        eprintln!("{} No samples received for {}. ", "[WARNING]".bold().yellow(), name);
+        return Ok(());
+    }
+    let each_point = 100 / (problem.tests.len() as u32);
+    for (input, output) in &problem.tests {
+        samples.add_sample(each_point, problem.time_limit, problem.memory_limit, input, output)?;
+    }
+    eprintln!("{}", format!("Created {} with {} sample(s). ", name, problem.tests.len()).green());
+    Ok(())
+}
+
+/// Listen on the port and handle the problems sent by Competitive Companion until the process is killed.
+pub fn listen(workspace: &Workspace, port: u16) -> Result<(), Option<String>> {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            return Err(Some(format!("Cannot listen on port {port}: {err}")));
+        }
+    };
+    eprintln!("Listening on port {} for Competitive Companion. Press Ctrl+C to stop. ", port.to_string().cyan());
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("{} Connection failed: {err}", "[WARNING]".bold().yellow());
+                continue;
+            }
+        };
+        let body = read_request_body(&stream);
+
+        // Always answer the extension so that it won't wait for us.
+        let status = body.as_ref().map(|_| "200 OK").unwrap_or_else(|bad| bad.status);
+        let _ = stream.write_all(format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").as_bytes());
+
+        let body = match body {
+            Ok(body) => body,
+            Err(bad) => {
+                eprintln!("{} {}", "[WARNING]".bold().yellow(), bad.message);
+                continue;
+            }
+        };
+        let result = CompanionProblem::from_json(&body)
+            .and_then(|problem| receive(workspace, &problem));
+        if let Err(Some(msg)) = result {
+            eprintln!("{}", format!("Error: {}", msg).bold().red());
+        }
+    }
+    Ok(())
+}
+
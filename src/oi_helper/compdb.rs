@@ -0,0 +1,57 @@
+//! This file contains the generation of `compile_commands.json`, which tells clangd and editors how sources are compiled.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use json::{object, JsonValue};
+
+/// Find the sources with the extensions in the directory, recursively.
+/// The build directory, hidden directories, sample groups and statement assets are skipped.
+pub fn find_sources(dir: &Path, extensions: &[&str]) -> Vec<PathBuf> {
+    let mut result = Vec::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return result,
+    };
+    let mut paths = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect::<Vec<PathBuf>>();
+    paths.sort();
+    for path in paths {
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        if path.is_dir() {
+            if name == "target" || name.starts_with('.') || name.ends_with(".smpd") || name.ends_with(".assets") {
+                continue;
+            }
+            result.extend(find_sources(&path, extensions));
+        } else if let Some(extension) = path.extension() {
+            if extensions.iter().any(|e| *e == extension.to_string_lossy()) {
+                result.push(path);
+            }
+        }
+    }
+    result
+}
+
+/// Get an entry of the compilation database. `command` is the compiler and its arguments.
+pub fn entry(command: &[String], source: &Path) -> JsonValue {
+    let mut arguments = JsonValue::new_array();
+    for arg in command {
+        let _ = arguments.push(arg.as_str());
+    }
+    object! {
+        "directory": source.parent().map(|dir| dir.to_string_lossy().to_string()).unwrap_or_default(),
+        "file": source.to_string_lossy().to_string(),
+        "arguments": arguments,
+    }
+}
+
+/// Write the entries into `root/compile_commands.json`.
+pub fn write(root: &Path, entries: Vec<JsonValue>) -> Result<(), Option<String>> {
+    let path = root.join("compile_commands.json");
+    let content = json::stringify_pretty(JsonValue::Array(entries), 2);
+    match fs::write(&path, content) {
+        Ok(_) => Ok(()),
+        Err(err) => Err(Some(format!("Cannot write {}: {}", path.display(), err))),
+    }
+}
@@ -0,0 +1,588 @@
+//! This file contains the typed configuration of workspaces and its schema.
+
+use std::collections::BTreeMap;
+
+use json::{object, JsonValue};
+
+/// The type of a configuration value.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ValueKind {
+    String,
+    UInt,
+    Bool,
+    /// An object whose values are strings. Each entry can be set with `key.entry`.
+    Table,
+}
+
+impl ValueKind {
+    fn name(&self) -> &'static str {
+        match self {
+            ValueKind::String => "a string",
+            ValueKind::UInt => "an unsigned integer",
+            ValueKind::Bool => "a boolean (true or false)",
+            ValueKind::Table => "an object of strings",
+        }
+    }
+
+    /// Parse a value given in the command line.
+    pub fn parse(&self, value: &str) -> Result<JsonValue, String> {
+        match self {
+            ValueKind::String => Ok(JsonValue::from(value)),
+            ValueKind::UInt => match value.trim().parse::<u64>() {
+                Ok(n) => Ok(JsonValue::from(n)),
+                Err(_) => Err(format!("expected {}, found `{}`", self.name(), value)),
+            },
+            ValueKind::Bool => match value.trim().to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok(JsonValue::from(true)),
+                "false" | "no" | "off" | "0" => Ok(JsonValue::from(false)),
+                _ => Err(format!("expected {}, found `{}`", self.name(), value)),
+            },
+            ValueKind::Table => match json::parse(value) {
+                Ok(table) => self.coerce(&table),
+                Err(_) => Err(format!("expected {}, found `{}`", self.name(), value)),
+            },
+        }
+    }
+
+    /// Check a value read from a configuration file. Numbers and booleans written as strings by older versions are accepted.
+    pub fn coerce(&self, value: &JsonValue) -> Result<JsonValue, String> {
+        match (self, value) {
+            (ValueKind::String, JsonValue::String(_) | JsonValue::Short(_)) => Ok(value.clone()),
+            (ValueKind::UInt, JsonValue::Number(_)) => match value.as_u64() {
+                Some(n) => Ok(JsonValue::from(n)),
+                None => Err(format!("expected {}, found {}", self.name(), value.dump())),
+            },
+            (ValueKind::Bool, JsonValue::Boolean(_)) => Ok(value.clone()),
+            (ValueKind::UInt | ValueKind::Bool, JsonValue::String(_) | JsonValue::Short(_)) => {
+                self.parse(value.as_str().unwrap_or(""))
+            }
+            (ValueKind::Table, JsonValue::Object(_)) => {
+                let mut table = JsonValue::new_object();
+                for (name, entry) in value.entries() {
+                    match entry.as_str() {
+                        Some(entry) => table[name] = JsonValue::from(entry),
+                        None => return Err(format!("expected a string for `{}`, found {}", name, entry.dump())),
+                    }
+                }
+                Ok(table)
+            }
+            _ => Err(format!("expected {}, found {}", self.name(), value.dump())),
+        }
+    }
+}
+
+/// A known configuration key.
+pub struct ConfigKey {
+    pub name: &'static str,
+    pub kind: ValueKind,
+    pub default: &'static str,
+    pub description: &'static str,
+    /// Internal keys are maintained by oi_helper and shouldn't be edited by hand.
+    pub internal: bool,
+    /// Global-only keys are read before any workspace is opened, so they can't be set in `oi_ws.json`.
+    pub global_only: bool,
+}
+
+const fn key(name: &'static str, kind: ValueKind, default: &'static str, description: &'static str) -> ConfigKey {
+    ConfigKey { name, kind, default, description, internal: false, global_only: false }
+}
+
+const fn internal_key(name: &'static str, kind: ValueKind, default: &'static str) -> ConfigKey {
+    ConfigKey { name, kind, default, description: "", internal: true, global_only: false }
+}
+
+const fn global_key(name: &'static str, kind: ValueKind, default: &'static str, description: &'static str) -> ConfigKey {
+    ConfigKey { name, kind, default, description, internal: false, global_only: true }
+}
+
+/// All the known configuration keys.
+pub static CONFIG_KEYS: &[ConfigKey] = &[
+    internal_key("initialzed", ValueKind::String, "true"),
+    internal_key("oi_helper_version", ValueKind::String, crate::VERSION),
+    internal_key("__unsafe_updating", ValueKind::Bool, "false"),
+    key("cc_compiler", ValueKind::String, "g++", "The C++ compiler."),
+    key("cc_flags", ValueKind::String, "-std=c++11 -O2 -Wall -xc++ ", "The flags passed to the C++ compiler."),
+    key("cc_template", ValueKind::String, "temp0", "The template scheme, `temp0` or `temp1`."),
+    key("cc_default_extension", ValueKind::String, "cc", "The extension of the created source files."),
+    key("author", ValueKind::String, "", "The author's name, which can be used in templates."),
+    key("sample_timeout", ValueKind::UInt, "1000", "The default time limit of created samples, in milliseconds."),
+    key("sample_memory_limit", ValueKind::UInt, "256", "The default memory limit of created samples, in megabytes."),
+    key("default_profile", ValueKind::String, "release", "The build profile used when --profile isn't given."),
+    key(
+        "profiles",
+        ValueKind::Table,
+        r#"{"release": "-O2", "debug": "-g -O0 -D__DEBUG__", "sanitize": "-g -fsanitize=address,undefined -D_GLIBCXX_DEBUG"}"#,
+        "The build profiles. Each of them is a string of flags added after `cc_flags`.",
+    ),
+    key("compdb_on_create", ValueKind::Bool, "false", "Regenerate compile_commands.json when a source file is created."),
+    global_key("net_timeout", ValueKind::UInt, "10", "The timeout of each web request, in seconds."),
+    global_key("net_retries", ValueKind::UInt, "2", "How many times a failed web request will be retried."),
+    global_key("net_proxy", ValueKind::String, "", "The proxy for web requests. Empty means no proxy."),
+    global_key("net_user_agent", ValueKind::String, concat!("oi_helper/", env!("CARGO_PKG_VERSION")), "The User-Agent of web requests."),
+    global_key("net_cache_ttl", ValueKind::UInt, "86400", "How long a cached web page is used, in seconds."),
+];
+
+/// Find a known key.
+pub fn find_key(name: &str) -> Option<&'static ConfigKey> {
+    CONFIG_KEYS.iter().find(|k| k.name == name)
+}
+
+/// Check if a key can only be set in the global configuration.
+pub fn is_global_only(name: &str) -> bool {
+    find_key(name).map(|k| k.global_only).unwrap_or(false)
+}
+
+/// Remove the global-only keys from a workspace configuration file, as they don't take effect there.
+pub fn without_global_only(json: &JsonValue) -> JsonValue {
+    let mut result = json.clone();
+    for k in CONFIG_KEYS.iter().filter(|k| k.global_only) {
+        result.remove(k.name);
+    }
+    result
+}
+
+/// The edit distance between two strings, used for suggesting keys.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
+    let mut dp = (0..=b.len()).collect::<Vec<usize>>();
+    for i in 1..=a.len() {
+        let mut prev = dp[0];
+        dp[0] = i;
+        for j in 1..=b.len() {
+            let cur = dp[j];
+            dp[j] = if a[i - 1] == b[j - 1] { prev } else { 1 + prev.min(dp[j]).min(dp[j - 1]) };
+            prev = cur;
+        }
+    }
+    dp[b.len()]
+}
+
+/// Suggest a known key for a mistyped one.
+pub fn suggest_key(name: &str) -> Option<&'static str> {
+    CONFIG_KEYS
+        .iter()
+        .filter(|k| !k.internal)
+        .map(|k| (edit_distance(name, k.name), k.name))
+        .filter(|(d, _)| *d <= 3)
+        .min()
+        .map(|(_, name)| name)
+}
+
+/// Split `table.entry` into the table key and the entry name, if the key is a table.
+fn split_entry(key: &str) -> Option<(&'static ConfigKey, &str)> {
+    let (table, entry) = key.split_once('.')?;
+    match find_key(table) {
+        Some(k) if k.kind == ValueKind::Table && !entry.is_empty() => Some((k, entry)),
+        _ => None,
+    }
+}
+
+/// Check a key-value pair given in the command line and convert the value into its type.
+/// Unknown and internal keys are rejected unless `force` is true, in which case the value is kept as a string.
+pub fn validate(key: &str, value: &str, force: bool) -> Result<JsonValue, String> {
+    if split_entry(key).is_some() {
+        return Ok(JsonValue::from(value));
+    }
+    match find_key(key) {
+        Some(k) if k.internal && !force => Err(format!(
+            "`{key}` is maintained by oi_helper. Use --force if you really want to change it. "
+        )),
+        Some(k) => k
+            .kind
+            .parse(value)
+            .map_err(|err| format!("Invalid value for `{key}`: {err}. ({})", k.description)),
+        None if force => Ok(JsonValue::from(value)),
+        None => Err(match suggest_key(key) {
+            Some(suggestion) => format!("Unknown configuration key `{key}`. Did you mean `{suggestion}`? "),
+            None => format!("Unknown configuration key `{key}`. Use --force to set it anyway. "),
+        }),
+    }
+}
+
+/// Check a key-value pair and set it in the configuration file `json`. `table.entry` sets an entry of a table.
+pub fn set_in(json: &mut JsonValue, key: &str, value: &str, force: bool) -> Result<(), String> {
+    let value = validate(key, value, force)?;
+    match split_entry(key) {
+        Some((table, entry)) => {
+            if !json[table.name].is_object() {
+                json[table.name] = JsonValue::new_object();
+            }
+            json[table.name][entry] = value;
+        }
+        None => json[key] = value,
+    }
+    Ok(())
+}
+
+/// Remove a key, or an entry of a table, from the configuration file `json`.
+pub fn unset_in(json: &mut JsonValue, key: &str) -> Result<(), String> {
+    if let Some(k) = find_key(key) {
+        if k.internal {
+            return Err(format!("`{key}` is maintained by oi_helper and cannot be unset. "));
+        }
+    }
+    let removed = match split_entry(key) {
+        Some((table, entry)) if json[table.name].has_key(entry) => {
+            json[table.name].remove(entry);
+            true
+        }
+        Some(_) => false,
+        None if json.has_key(key) => {
+            json.remove(key);
+            true
+        }
+        None => false,
+    };
+    if removed {
+        Ok(())
+    } else {
+        Err(format!("`{key}` is not set in this configuration file. "))
+    }
+}
+
+/// Get the value of `key` in the configuration file `json`. `table.entry` gets an entry of a table.
+pub fn get_in(json: &JsonValue, key: &str) -> Option<JsonValue> {
+    let value = match split_entry(key) {
+        Some((table, entry)) => &json[table.name][entry],
+        None => &json[key],
+    };
+    if value.is_null() {
+        None
+    } else {
+        Some(value.clone())
+    }
+}
+
+/// Where a configuration value comes from.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Origin {
+    Builtin,
+    Global,
+    Workspace,
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Origin::Builtin => "builtin",
+            Origin::Global => "global.json",
+            Origin::Workspace => "oi_ws.json",
+        })
+    }
+}
+
+/// The typed configuration of a workspace.
+pub struct WorkspaceConfig {
+    pub initialized: String,
+    pub oi_helper_version: String,
+    pub unsafe_updating: bool,
+    pub cc_compiler: String,
+    pub cc_flags: String,
+    pub cc_template: String,
+    pub cc_default_extension: String,
+    pub author: String,
+    pub sample_timeout: u32,
+    pub sample_memory_limit: u32,
+    pub default_profile: String,
+    /// The flags of each build profile.
+    pub profiles: BTreeMap<String, String>,
+    pub compdb_on_create: bool,
+    pub net_timeout: u64,
+    pub net_retries: u32,
+    pub net_proxy: String,
+    pub net_user_agent: String,
+    pub net_cache_ttl: u64,
+    /// Unknown keys, which were set with --force or written by a newer version.
+    pub extra: JsonValue,
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        let mut config = Self {
+            initialized: String::new(),
+            oi_helper_version: String::new(),
+            unsafe_updating: false,
+            cc_compiler: String::new(),
+            cc_flags: String::new(),
+            cc_template: String::new(),
+            cc_default_extension: String::new(),
+            author: String::new(),
+            sample_timeout: 0,
+            sample_memory_limit: 0,
+            default_profile: String::new(),
+            profiles: BTreeMap::new(),
+            compdb_on_create: false,
+            net_timeout: 0,
+            net_retries: 0,
+            net_proxy: String::new(),
+            net_user_agent: String::new(),
+            net_cache_ttl: 0,
+            extra: JsonValue::new_object(),
+        };
+        for k in CONFIG_KEYS {
+            // The builtin defaults are always valid.
+            config.assign(k.name, &k.kind.parse(k.default).unwrap());
+        }
+        config
+    }
+}
+
+impl WorkspaceConfig {
+    /// Build the configuration from `json`. Keys missing in `json` are taken from `fallback`, then the builtin defaults.
+    /// All the problems found are returned as a list of messages.
+    pub fn from_json(json: &JsonValue, fallback: &JsonValue) -> Result<Self, Vec<String>> {
+        let mut config = Self::default();
+        let mut errors = Vec::new();
+        config.merge(fallback, &mut errors);
+        config.merge(json, &mut errors);
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Merge the values in `json` into the configuration.
+    fn merge(&mut self, json: &JsonValue, errors: &mut Vec<String>) {
+        if json.is_null() {
+            return;
+        }
+        if !json.is_object() {
+            errors.push(format!("expected a JSON object, found {}", json.dump()));
+            return;
+        }
+        for (name, value) in json.entries() {
+            match find_key(name) {
+                // The entries of tables are merged instead of replacing the whole table.
+                Some(k) if k.kind == ValueKind::Table => match k.kind.coerce(value) {
+                    Ok(value) => self.merge_table(name, &value),
+                    Err(err) => errors.push(format!("`{name}`: {err}")),
+                },
+                Some(k) => match k.kind.coerce(value) {
+                    Ok(value) => self.assign(name, &value),
+                    Err(err) => errors.push(format!("`{name}`: {err}")),
+                },
+                None => self.extra[name] = value.clone(),
+            }
+        }
+    }
+
+    /// Add the entries to a table.
+    fn merge_table(&mut self, name: &str, value: &JsonValue) {
+        if name == "profiles" {
+            for (entry, flags) in value.entries() {
+                self.profiles.insert(entry.to_string(), flags.as_str().unwrap_or("").to_string());
+            }
+        }
+    }
+
+    /// Assign a value which has been converted into the type of the key.
+    pub fn assign(&mut self, name: &str, value: &JsonValue) {
+        let string = || value.as_str().unwrap_or("").to_string();
+        let uint = || value.as_u64().unwrap_or(0);
+        match name {
+            "initialzed" => self.initialized = string(),
+            "oi_helper_version" => self.oi_helper_version = string(),
+            "__unsafe_updating" => self.unsafe_updating = value.as_bool().unwrap_or(false),
+            "cc_compiler" => self.cc_compiler = string(),
+            "cc_flags" => self.cc_flags = string(),
+            "cc_template" => self.cc_template = string(),
+            "cc_default_extension" => self.cc_default_extension = string(),
+            "author" => self.author = string(),
+            "sample_timeout" => self.sample_timeout = uint() as u32,
+            "sample_memory_limit" => self.sample_memory_limit = uint() as u32,
+            "default_profile" => self.default_profile = string(),
+            "profiles" => {
+                self.profiles.clear();
+                self.merge_table(name, value);
+            }
+            "compdb_on_create" => self.compdb_on_create = value.as_bool().unwrap_or(false),
+            "net_timeout" => self.net_timeout = uint(),
+            "net_retries" => self.net_retries = uint() as u32,
+            "net_proxy" => self.net_proxy = string(),
+            "net_user_agent" => self.net_user_agent = string(),
+            "net_cache_ttl" => self.net_cache_ttl = uint(),
+            _ => self.extra[name] = value.clone(),
+        }
+    }
+
+    /// Get the value of a key. `table.entry` gets an entry of a table.
+    pub fn get(&self, name: &str) -> Option<JsonValue> {
+        if let Some((table, entry)) = split_entry(name) {
+            return get_in(&self.get(table.name)?, &format!("{}.{}", table.name, entry));
+        }
+        Some(match name {
+            "initialzed" => JsonValue::from(self.initialized.as_str()),
+            "oi_helper_version" => JsonValue::from(self.oi_helper_version.as_str()),
+            "__unsafe_updating" => JsonValue::from(self.unsafe_updating),
+            "cc_compiler" => JsonValue::from(self.cc_compiler.as_str()),
+            "cc_flags" => JsonValue::from(self.cc_flags.as_str()),
+            "cc_template" => JsonValue::from(self.cc_template.as_str()),
+            "cc_default_extension" => JsonValue::from(self.cc_default_extension.as_str()),
+            "author" => JsonValue::from(self.author.as_str()),
+            "sample_timeout" => JsonValue::from(self.sample_timeout),
+            "sample_memory_limit" => JsonValue::from(self.sample_memory_limit),
+            "default_profile" => JsonValue::from(self.default_profile.as_str()),
+            "profiles" => {
+                let mut table = JsonValue::new_object();
+                for (entry, flags) in &self.profiles {
+                    table[entry.as_str()] = JsonValue::from(flags.as_str());
+                }
+                table
+            }
+            "compdb_on_create" => JsonValue::from(self.compdb_on_create),
+            "net_timeout" => JsonValue::from(self.net_timeout),
+            "net_retries" => JsonValue::from(self.net_retries),
+            "net_proxy" => JsonValue::from(self.net_proxy.as_str()),
+            "net_user_agent" => JsonValue::from(self.net_user_agent.as_str()),
+            "net_cache_ttl" => JsonValue::from(self.net_cache_ttl),
+            _ => {
+                if self.extra.has_key(name) {
+                    self.extra[name].clone()
+                } else {
+                    return None;
+                }
+            }
+        })
+    }
+
+    /// Get the flags of a build profile.
+    pub fn profile(&self, name: &str) -> Result<&str, String> {
+        match self.profiles.get(name) {
+            Some(flags) => Ok(flags),
+            None => Err(format!(
+                "Unknown build profile `{}`. Available profiles: {}. ",
+                name,
+                self.profiles.keys().cloned().collect::<Vec<String>>().join(", ")
+            )),
+        }
+    }
+
+    /// Get the names of all the keys, including the unknown ones.
+    pub fn keys(&self) -> Vec<String> {
+        CONFIG_KEYS
+            .iter()
+            .map(|k| k.name.to_string())
+            .chain(self.extra.entries().map(|(name, _)| name.to_string()))
+            .collect()
+    }
+
+    /// Convert the configuration into JSON, which will be saved as `oi_ws.json`.
+    pub fn to_json(&self) -> JsonValue {
+        let mut result = object! {};
+        for k in CONFIG_KEYS {
+            if let Some(value) = self.get(k.name) {
+                result[k.name] = value;
+            }
+        }
+        for (name, value) in self.extra.entries() {
+            result[name] = value.clone();
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_values() {
+        assert_eq!(ValueKind::UInt.parse(" 42 ").unwrap(), 42);
+        assert!(ValueKind::UInt.parse("-1").is_err());
+        assert!(ValueKind::UInt.parse("1.5").is_err());
+        for (value, expected) in [("true", true), ("on", true), ("1", true), ("No", false), ("off", false), ("0", false)] {
+            assert_eq!(ValueKind::Bool.parse(value).unwrap(), expected);
+        }
+        assert!(ValueKind::Bool.parse("maybe").is_err());
+        assert_eq!(ValueKind::Table.parse(r#"{"fast": "-O3"}"#).unwrap(), object! { "fast": "-O3" });
+        assert!(ValueKind::Table.parse(r#"{"fast": 3}"#).is_err());
+        assert!(ValueKind::Table.parse("-O3").is_err());
+    }
+
+    #[test]
+    fn coerce_values() {
+        // Older versions wrote numbers and booleans as strings.
+        assert_eq!(ValueKind::UInt.coerce(&JsonValue::from("1000")).unwrap(), 1000);
+        assert_eq!(ValueKind::Bool.coerce(&JsonValue::from("true")).unwrap(), true);
+        assert_eq!(ValueKind::String.coerce(&JsonValue::from("g++")).unwrap(), "g++");
+        assert!(ValueKind::String.coerce(&JsonValue::from(1)).is_err());
+        assert!(ValueKind::UInt.coerce(&JsonValue::from(-1)).is_err());
+        assert!(ValueKind::Bool.coerce(&JsonValue::from(1)).is_err());
+        assert!(ValueKind::Table.coerce(&JsonValue::from("x")).is_err());
+    }
+
+    #[test]
+    fn validate_values() {
+        assert_eq!(validate("sample_timeout", "2000", false).unwrap(), 2000);
+        let err = validate("sample_timeout", "2s", false).unwrap_err();
+        assert!(err.contains("expected an unsigned integer, found `2s`"), "{err}");
+        assert!(validate("compdb_on_create", "sometimes", false).is_err());
+        assert_eq!(validate("profiles.fast", "-O3", false).unwrap(), "-O3");
+
+        // Internal keys and unknown keys need --force.
+        assert!(validate("oi_helper_version", "1.0.0", false).is_err());
+        assert!(validate("oi_helper_version", "1.0.0", true).is_ok());
+        let err = validate("cc_flag", "-O2", false).unwrap_err();
+        assert!(err.contains("Did you mean `cc_flags`?"), "{err}");
+        assert_eq!(validate("my_key", "x", true).unwrap(), "x");
+    }
+
+    #[test]
+    fn suggest_keys() {
+        assert_eq!(suggest_key("cc_flag"), Some("cc_flags"));
+        assert_eq!(suggest_key("autor"), Some("author"));
+        assert_eq!(suggest_key("net_timout"), Some("net_timeout"));
+        assert_eq!(suggest_key("something_else_entirely"), None);
+        // Internal keys are never suggested.
+        assert_eq!(suggest_key("initialized"), None);
+    }
+
+    #[test]
+    fn set_and_unset() {
+        let mut json = object! {};
+        set_in(&mut json, "sample_timeout", "2000", false).unwrap();
+        set_in(&mut json, "profiles.fast", "-O3", false).unwrap();
+        set_in(&mut json, "profiles.slow", "-O0", false).unwrap();
+        assert_eq!(json, object! { "sample_timeout": 2000, "profiles": { "fast": "-O3", "slow": "-O0" } });
+        assert!(set_in(&mut json, "sample_timeout", "soon", false).is_err());
+        assert_eq!(json["sample_timeout"], 2000);
+
+        assert_eq!(get_in(&json, "profiles.fast"), Some(JsonValue::from("-O3")));
+        assert_eq!(get_in(&json, "profiles.none"), None);
+
+        unset_in(&mut json, "profiles.fast").unwrap();
+        unset_in(&mut json, "sample_timeout").unwrap();
+        assert_eq!(json, object! { "profiles": { "slow": "-O0" } });
+        assert!(unset_in(&mut json, "sample_timeout").is_err());
+        assert!(unset_in(&mut json, "oi_helper_version").is_err());
+    }
+
+    #[test]
+    fn global_only_keys() {
+        assert!(is_global_only("net_timeout"));
+        assert!(!is_global_only("cc_flags"));
+        assert!(!is_global_only("unknown"));
+        let json = object! { "net_timeout": 5, "net_proxy": "x", "cc_flags": "-O2" };
+        assert_eq!(without_global_only(&json), object! { "cc_flags": "-O2" });
+    }
+
+    #[test]
+    fn layered_config() {
+        let global = object! { "author": "global", "sample_timeout": 2000, "profiles": { "fast": "-O3" } };
+        let local = object! { "author": "local", "profiles": { "release": "-O1" } };
+        let config = WorkspaceConfig::from_json(&local, &global).unwrap();
+        assert_eq!(config.author, "local");
+        assert_eq!(config.sample_timeout, 2000);
+        assert_eq!(config.cc_compiler, "g++");
+        // The entries of tables are merged.
+        assert_eq!(config.profile("fast").unwrap(), "-O3");
+        assert_eq!(config.profile("release").unwrap(), "-O1");
+        assert!(config.profile("debug").is_ok());
+        assert!(config.profile("none").is_err());
+
+        let errors = WorkspaceConfig::from_json(&object! { "sample_timeout": "soon", "compdb_on_create": 1 }, &JsonValue::Null).err().unwrap();
+        assert_eq!(errors.len(), 2);
+    }
+}
@@ -0,0 +1,79 @@
+//! This file contains the `config` and `global-config` subcommands.
+
+use crossterm::style::Stylize;
+use json::{object, JsonValue};
+
+use crate::ConfigSubcommand;
+
+use super::{
+    config::{self, Origin},
+    workspace::Workspace,
+};
+
+/// Print the entries as `key = value [origin]`, skipping the internal keys.
+fn print_entries(entries: &[(String, JsonValue, Origin)]) {
+    let width = entries.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+    for (key, value, origin) in entries {
+        if config::find_key(key).map(|k| k.internal).unwrap_or(false) {
+            continue;
+        }
+        println!("{:width$} = {} {}", key, value.dump(), format!("[{}]", origin).dark_grey());
+    }
+}
+
+/// Find the value of `key` in the entries.
+fn get_entry(entries: Vec<(String, JsonValue, Origin)>, key: &str) -> Result<JsonValue, Option<String>> {
+    // `table.entry` is looked up in the table.
+    let table = key.split_once('.').map(|(table, _)| table).unwrap_or(key);
+    let found = entries
+        .into_iter()
+        .find(|(name, _, _)| name == key || name == table)
+        .and_then(|(name, value, _)| if name == key { Some(value) } else { config::get_in(&object! { [name.as_str()]: value }, key) });
+    match found {
+        Some(value) => Ok(value),
+        None => Err(Some(match config::suggest_key(key) {
+            Some(suggestion) => format!("Unknown configuration key `{key}`. Did you mean `{suggestion}`? "),
+            None => format!("Unknown configuration key `{key}`. "),
+        })),
+    }
+}
+
+/// Configure the workspace.
+pub fn workspace_config(workspace: &mut Workspace, subcommand: &ConfigSubcommand) -> Result<(), Option<String>> {
+    match subcommand {
+        ConfigSubcommand::Set { key, value, force } => {
+            workspace.set_config(key, value, *force)?;
+            workspace.save_config()?;
+        }
+
+        ConfigSubcommand::Get { key } => {
+            // Print strings without quotes, so the output can be used in scripts.
+            println!("{}", get_entry(workspace.entries(), key)?);
+        }
+
+        ConfigSubcommand::List => print_entries(&workspace.entries()),
+
+        ConfigSubcommand::Unset { key } => {
+            workspace.unset_config(key)?;
+            workspace.save_config()?;
+            if let Some(value) = workspace.config().get(key) {
+                eprintln!("`{}` is now {} [{}]", key, value.dump(), workspace.origin(key));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Edit the global configuration in the directory `global_cfg`.
+pub fn global_config(global_cfg: &Option<String>, subcommand: &ConfigSubcommand) -> Result<(), Option<String>> {
+    match subcommand {
+        ConfigSubcommand::Set { key, value, force } => Workspace::set_g_config(global_cfg, key, value, *force)?,
+
+        ConfigSubcommand::Get { key } => println!("{}", get_entry(Workspace::global_entries(global_cfg)?, key)?),
+
+        ConfigSubcommand::List => print_entries(&Workspace::global_entries(global_cfg)?),
+
+        ConfigSubcommand::Unset { key } => Workspace::unset_g_config(global_cfg, key)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! This file contains the contest directories of official contests like CSP and NOIP,
+//! where every problem `p` is submitted as `ID/p/p.cpp`.
+
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crossterm::style::Stylize;
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use super::{bundle, checker, samples::Samples, workspace::Workspace};
+
+/// The extension required by the judges.
+const EXTENSION: &str = "cpp";
+
+/// Check if a name can be used as the contest ID or a problem name, which are also directory and file names.
+fn check_name(kind: &str, name: &str) -> Result<(), String> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(format!("Invalid {kind} `{name}`, only letters, digits, `_` and `-` are allowed. "));
+    }
+    Ok(())
+}
+
+/// Get the path to the source of a problem, e.g. `ID/p/p.cpp`.
+fn source_path(dir: &Path, problem: &str) -> PathBuf {
+    dir.join(problem).join(format!("{problem}.{EXTENSION}"))
+}
+
+/// Create the contest directory `id` with a source file and a sample group for every problem.
+/// The sample groups use file IO, since the problems of official contests do.
+pub fn init(workspace: &Workspace, id: &str, problems: &[String], template: &str) -> Result<(), Option<String>> {
+    check_name("contest ID", id)?;
+    for (i, problem) in problems.iter().enumerate() {
+        check_name("problem name", problem)?;
+        if problems[..i].contains(problem) {
+            return Err(Some(format!("Problem {problem} is given more than once. ")));
+        }
+    }
+    let dir = Path::new(id);
+    if dir.exists() && !dir.is_dir() {
+        return Err(Some(format!("Cannot create the contest because the filename {id} has been used. ")));
+    }
+
+    for problem in problems {
+        let problem_dir = dir.join(problem);
+        if let Err(err) = fs::create_dir_all(&problem_dir) {
+            return Err(Some(format!("Cannot create {}: {}", problem_dir.display(), err)));
+        }
+        let source = source_path(dir, problem);
+
+        // Set up the sample group first, so the template knows the problem uses file IO.
+        let mut samples = Samples::open_group(&source.with_extension("").to_string_lossy())?;
+        if samples.file_io().is_none() {
+            samples.set_file_io(Some(problem))?;
+        }
+
+        if source.exists() {
+            eprintln!("{} {} already exists, skipped. ", "[WARNING]".bold().yellow(), source.display());
+        } else {
+            workspace.create_source(&source.to_string_lossy(), template, "1e5+114514", "128", false, &[])?;
+            println!("Created {}", source.display());
+        }
+    }
+    println!(
+        "{}",
+        format!("Contest {id} is ready. Test a problem with `oi_helper test {id}/P/P.cpp`, and pack the contest with `oi_helper contest pack {id}`. ").green()
+    );
+    Ok(())
+}
+
+/// Find the problems of a contest directory, which are the subdirectories.
+fn problems(dir: &Path) -> Result<Vec<String>, Option<String>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) => return Err(Some(format!("Cannot read {}: {}", dir.display(), err))),
+    };
+    let mut problems = Vec::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if entry.path().is_dir() {
+            problems.push(name);
+        } else {
+            eprintln!("{} {} isn't a problem directory and won't be packed. ", "[WARNING]".bold().yellow(), entry.path().display());
+        }
+    }
+    problems.sort();
+    Ok(problems)
+}
+
+/// Check every problem of the contest directory, and write the sources into `ID.zip` next to it.
+/// The sources are bundled, so local includes and the debug kit are handled.
+/// Returns the path to the archive.
+pub fn pack(workspace: &Workspace, dir: &Path, allow_optimize: bool) -> Result<PathBuf, Option<String>> {
+    let dir = match fs::canonicalize(dir) {
+        Ok(dir) if dir.is_dir() => dir,
+        _ => return Err(Some(format!("{} isn't a contest directory. ", dir.display()))),
+    };
+    let id = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+    check_name("contest ID", &id)?;
+    let problems = problems(&dir)?;
+    if problems.is_empty() {
+        return Err(Some(format!("No problems found in {}. Create them with `oi_helper contest init`. ", dir.display())));
+    }
+
+    // Check all the problems before giving up, so every mistake is reported at once.
+    let mut failed = Vec::new();
+    let mut sources = Vec::new();
+    for problem in &problems {
+        let source = source_path(&dir, problem);
+        if let Err(err) = check_name("problem name", problem) {
+            println!("{} {}", "[ERROR]".bold().red(), err);
+            failed.push(problem.clone());
+            continue;
+        }
+        if !source.is_file() {
+            println!("{} {} is missing. ", "[ERROR]".bold().red(), source.display());
+            failed.push(problem.clone());
+            continue;
+        }
+        let options = checker::Options {
+            file_io: Some(problem.clone()),
+            allow_optimize,
+        };
+        if workspace.check(&source.to_string_lossy(), &options).is_err() {
+            failed.push(problem.clone());
+            continue;
+        }
+        sources.push((problem, bundle::bundle(&source)?));
+    }
+    if !failed.is_empty() {
+        return Err(Some(format!("{} failed the checks, so the archive isn't written. ", failed.join(", "))));
+    }
+
+    let archive = dir.with_extension("zip");
+    let result = File::create(&archive).map_err(|err| err.to_string()).and_then(|file| {
+        let mut zip = ZipWriter::new(file);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        zip.add_directory(format!("{id}/"), options).map_err(|err| err.to_string())?;
+        for (problem, content) in &sources {
+            zip.add_directory(format!("{id}/{problem}/"), options).map_err(|err| err.to_string())?;
+            zip.start_file(format!("{id}/{problem}/{problem}.{EXTENSION}"), options).map_err(|err| err.to_string())?;
+            zip.write_all(content.as_bytes()).map_err(|err| err.to_string())?;
+        }
+        zip.finish().map_err(|err| err.to_string())?;
+        Ok(())
+    });
+    if let Err(err) = result {
+        return Err(Some(format!("Cannot write {}: {}", archive.display(), err)));
+    }
+    Ok(archive)
+}
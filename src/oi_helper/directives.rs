@@ -0,0 +1,215 @@
+//! This file contains the directives written in the comments at the top of source files, like
+//! `// oi_helper: flags=-std=c++20 stack=512M timeout=2000`.
+
+use std::{fs, path::Path, process::Command};
+
+use crossterm::style::Stylize;
+
+/// The stack size of the program.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StackSize {
+    Unlimited,
+    KiB(u64),
+}
+
+impl StackSize {
+    /// Parse a size like `512M`, `65536K`, `1G` or `unlimited`. A size without unit is in bytes.
+    fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("unlimited") {
+            return Ok(StackSize::Unlimited);
+        }
+        let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+        let (number, unit) = value.split_at(split);
+        let number = number.parse::<u64>().map_err(|_| format!("invalid stack size `{value}`"))?;
+        let kib = match unit.to_uppercase().trim_end_matches('B') {
+            "" => number.div_ceil(1024),
+            "K" => number,
+            "M" => number * 1024,
+            "G" => number * 1024 * 1024,
+            _ => return Err(format!("invalid unit of stack size `{value}`, expected K, M or G")),
+        };
+        Ok(StackSize::KiB(kib))
+    }
+}
+
+/// The directives of a source file.
+#[derive(Default)]
+pub struct Directives {
+    /// Flags added after the workspace's flags and the build profile's flags.
+    pub flags: Vec<String>,
+    pub stack: Option<StackSize>,
+    /// The time limit in milliseconds, which overrides the ones of the samples.
+    pub timeout: Option<u32>,
+}
+
+/// Split a directive into `key=value` words. Double quotes can be used for values containing spaces.
+fn split_words(line: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut buffer = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !buffer.is_empty() {
+                    result.push(std::mem::take(&mut buffer));
+                }
+            }
+            c => buffer.push(c),
+        }
+    }
+    if !buffer.is_empty() {
+        result.push(buffer);
+    }
+    result
+}
+
+impl Directives {
+    /// Parse the directives in the comments at the top of the source. The scanning stops at the first line of code.
+    /// `comment` is the prefix of line comments in the language, e.g. `//` or `#`.
+    pub fn parse(source: &str, comment: &str) -> Result<Self, String> {
+        let mut result = Self::default();
+        for line in source.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            let text = match line.strip_prefix(comment) {
+                Some(text) => text.trim(),
+                None => break,
+            };
+            let directive = match text.strip_prefix("oi_helper:") {
+                Some(directive) => directive,
+                None => continue,
+            };
+            for word in split_words(directive) {
+                let (key, value) = match word.split_once('=') {
+                    Some(pair) => pair,
+                    None => return Err(format!("expected `key=value` in directive, found `{word}`")),
+                };
+                match key {
+                    "flags" => result.flags.extend(value.split_whitespace().map(String::from)),
+                    "stack" => result.stack = Some(StackSize::parse(value)?),
+                    "timeout" => {
+                        result.timeout = Some(value.parse().map_err(|_| format!("invalid timeout `{value}`, expected milliseconds"))?)
+                    }
+                    _ => eprintln!("{} Unknown directive `{}`, ignored. ", "[WARNING]".bold().yellow(), key),
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Read the directives of a source file.
+    pub fn from_file(path: &Path, comment: &str) -> Result<Self, Option<String>> {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => {
+                return Err(Some(format!("Cannot read {}: {}", path.display(), err)));
+            }
+        };
+        let result = Self::parse(&source, comment).map_err(|err| Some(format!("Bad directive in {}: {}", path.display(), err)))?;
+        if crate::is_debug() {
+            println!(
+                "[DEBUG] Directives of {}: flags={:?} timeout={:?}",
+                path.display(),
+                result.flags,
+                result.timeout
+            );
+        }
+        Ok(result)
+    }
+
+    /// Get the flags passed to the compiler, including the ones for the stack size on Windows.
+    pub fn compile_flags(&self) -> Vec<String> {
+        let mut flags = self.flags.clone();
+        if cfg!(windows) {
+            // The stack size is decided when linking on Windows.
+            if let Some(StackSize::KiB(kib)) = self.stack {
+                flags.push(format!("-Wl,--stack,{}", kib * 1024));
+            }
+        }
+        flags
+    }
+
+    /// Get the command running the program with the stack size applied.
+    /// `run` is the program and its arguments, which shouldn't be empty.
+    pub fn command(&self, run: &[String]) -> Command {
+        match self.stack {
+            Some(stack) if cfg!(unix) => {
+                let limit = match stack {
+                    StackSize::Unlimited => String::from("unlimited"),
+                    StackSize::KiB(kib) => kib.to_string(),
+                };
+                // If the hard limit is lower, `ulimit` fails and prints a message, but the program is still run.
+                let mut command = Command::new("sh");
+                command.arg("-c").arg(format!("ulimit -s {limit}; exec \"$@\"")).arg("sh").args(run);
+                command
+            }
+            _ => {
+                let mut command = Command::new(&run[0]);
+                command.args(&run[1..]);
+                command
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stack_sizes() {
+        assert_eq!(StackSize::parse("512M").unwrap(), StackSize::KiB(512 * 1024));
+        assert_eq!(StackSize::parse("1G").unwrap(), StackSize::KiB(1024 * 1024));
+        assert_eq!(StackSize::parse("65536K").unwrap(), StackSize::KiB(65536));
+        assert_eq!(StackSize::parse("256mb").unwrap(), StackSize::KiB(256 * 1024));
+        assert_eq!(StackSize::parse("1025").unwrap(), StackSize::KiB(2));
+        assert_eq!(StackSize::parse("unlimited").unwrap(), StackSize::Unlimited);
+        assert_eq!(StackSize::parse("Unlimited").unwrap(), StackSize::Unlimited);
+    }
+
+    #[test]
+    fn invalid_stack_sizes() {
+        assert!(StackSize::parse("").is_err());
+        assert!(StackSize::parse("M").is_err());
+        assert!(StackSize::parse("-1M").is_err());
+        assert!(StackSize::parse("1.5G").is_err());
+        assert!(StackSize::parse("512T").is_err());
+        assert!(Directives::parse("// oi_helper: stack=lots", "//").is_err());
+    }
+
+    #[test]
+    fn directives() {
+        let source = "// oi_helper: flags=\"-std=c++20 -O2\" stack=512M\n//oi_helper: timeout=2000 flags=-DLOCAL\nint main() {}\n";
+        let directives = Directives::parse(source, "//").unwrap();
+        assert_eq!(directives.flags, ["-std=c++20", "-O2", "-DLOCAL"]);
+        assert_eq!(directives.stack, Some(StackSize::KiB(512 * 1024)));
+        assert_eq!(directives.timeout, Some(2000));
+
+        let directives = Directives::parse("# oi_helper: timeout=500\nprint(1)\n", "#").unwrap();
+        assert_eq!(directives.timeout, Some(500));
+        assert!(directives.flags.is_empty());
+        assert_eq!(directives.stack, None);
+    }
+
+    #[test]
+    fn invalid_directives() {
+        assert!(Directives::parse("// oi_helper: timeout=2s", "//").is_err());
+        assert!(Directives::parse("// oi_helper: timeout=-1", "//").is_err());
+        assert!(Directives::parse("// oi_helper: timeout", "//").is_err());
+        // Unknown keys are only warned about.
+        assert!(Directives::parse("// oi_helper: memory=256M", "//").is_ok());
+    }
+
+    #[test]
+    fn stops_at_code() {
+        let source = "\n// A+B Problem\n\n// oi_helper: timeout=1000\n#include <cstdio>\n// oi_helper: timeout=2000 stack=bad\n";
+        let directives = Directives::parse(source, "//").unwrap();
+        assert_eq!(directives.timeout, Some(1000));
+        assert_eq!(directives.stack, None);
+        // Directives in other kinds of comments are not read.
+        assert_eq!(Directives::parse("/* oi_helper: timeout=1000 */", "//").unwrap().timeout, None);
+    }
+}
@@ -0,0 +1,93 @@
+//! This file contains functions for setting up a whole contest or problem list at once.
+
+use std::{
+    collections::VecDeque,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use crossterm::style::Stylize;
+
+use super::{
+    samples::Samples,
+    utils::web::{self, FetchedProblem, ProblemEntry},
+    workspace::Workspace,
+};
+
+/// Create the source file and the sample group for a fetched problem.
+fn set_up(workspace: &Workspace, name: &str, problem: &FetchedProblem) -> Result<(), Option<String>> {
+    if workspace.source_exists(name) {
+        eprintln!("{} Source file of {} already exists, skipped. ", "[WARNING]".bold().yellow(), name);
+    } else {
+        let vars = [
+            (String::from("url"), problem.url.clone()),
+            (String::from("title"), problem.title.clone()),
+        ];
+        workspace.create_source(name, "default", "1e5+114514", "128", false, &vars)?;
+    }
+
+    let mut samples = Samples::open_group(name)?;
+    if !samples.is_empty() {
+        eprintln!("{} Sample group of {} is not empty, skipped. ", "[WARNING]".bold().yellow(), name);
+        return Ok(());
+    }
+    samples.load_problem(problem)
+}
+
+/// Fetch every problem of the contest or problem list `spec` with `jobs` threads,
+/// and create a source file and a sample group for each of them.
+pub fn fetch_list(workspace: &Workspace, spec: &str, jobs: usize) -> Result<(), Option<String>> {
+    eprintln!("Fetching the problem list of {}... ", spec);
+    let entries = match web::fetch_problem_list(spec) {
+        Ok(entries) => entries,
+        Err(err) => {
+            return Err(Some(format!("Error occured while fetching the problem list: {}", err)));
+        }
+    };
+    let total = entries.len();
+    eprintln!("Found {} problem(s): {}", total, entries.iter().map(|e| e.name.as_str()).collect::<Vec<&str>>().join(", "));
+
+    // The workers fetch the problems, while this thread writes the files.
+    let queue = Arc::new(Mutex::new(entries.into_iter().collect::<VecDeque<ProblemEntry>>()));
+    let (sender, receiver) = mpsc::channel();
+    let mut workers = Vec::new();
+    for _ in 0..jobs.clamp(1, total.max(1)) {
+        let queue = Arc::clone(&queue);
+        let sender = sender.clone();
+        workers.push(thread::spawn(move || loop {
+            let entry = match queue.lock().unwrap().pop_front() {
+                Some(entry) => entry,
+                None => break,
+            };
+            let result = web::fetch_problem(&entry.problem).map_err(|err| err.to_string());
+            if sender.send((entry, result)).is_err() {
+                break;
+            }
+        }));
+    }
+    drop(sender);
+
+    let mut failed = Vec::new();
+    for (finished, (entry, result)) in receiver.into_iter().enumerate() {
+        let progress = format!("[{}/{}]", finished + 1, total);
+        let result = result
+            .map_err(Some)
+            .and_then(|problem| set_up(workspace, &entry.name, &problem));
+        match result {
+            Ok(_) => eprintln!("{} {}", progress.bold().green(), format!("{} is ready. ", entry.name).green()),
+            Err(err) => {
+                eprintln!("{} {}", progress.bold().red(), format!("Failed to set up {}: {}", entry.name, err.unwrap_or_default()).red());
+                failed.push(entry.name);
+            }
+        }
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(Some(format!("Failed to set up {} problem(s): {}", failed.len(), failed.join(", "))))
+    }
+}
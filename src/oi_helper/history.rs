@@ -0,0 +1,213 @@
+//! This file contains the test history of a problem, saved in its sample group:
+//! `history.jsonl` has a line for every test, and `history/` keeps the tested sources by their hashes.
+
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crossterm::style::Stylize;
+use json::{object, JsonValue};
+
+use super::{
+    problem::ProblemInfo,
+    utils::{datetime, hash},
+};
+
+/// The verdict of a sample.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Accepted,
+    WrongAnswer,
+    TimeLimitExceeded,
+    /// The program didn't write the output file of a file IO problem.
+    MissingOutputFile,
+}
+
+impl Verdict {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "AC" => Some(Self::Accepted),
+            "WA" => Some(Self::WrongAnswer),
+            "TLE" => Some(Self::TimeLimitExceeded),
+            "MOF" => Some(Self::MissingOutputFile),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Accepted => write!(f, "AC"),
+            Self::WrongAnswer => write!(f, "WA"),
+            Self::TimeLimitExceeded => write!(f, "TLE"),
+            Self::MissingOutputFile => write!(f, "MOF"),
+        }
+    }
+}
+
+/// The result of running a sample.
+pub struct SampleResult {
+    pub verdict: Verdict,
+    /// The running time in milliseconds.
+    pub time_ms: u64,
+    /// The peak memory in KB, if it could be measured.
+    pub memory_kb: Option<u64>,
+    /// The points got.
+    pub points: u32,
+}
+
+/// A test of the problem.
+pub struct Entry {
+    /// When the test was run, in seconds since the Unix epoch.
+    pub time: u64,
+    /// The hash of the tested source, which is also the name of its snapshot.
+    pub source_hash: String,
+    pub samples: Vec<SampleResult>,
+}
+
+impl Entry {
+    pub fn points(&self) -> u32 {
+        self.samples.iter().map(|sample| sample.points).sum()
+    }
+
+    /// Check if every sample passed.
+    pub fn passed(&self) -> bool {
+        self.samples.iter().all(|sample| sample.verdict == Verdict::Accepted)
+    }
+
+    /// Find the samples which passed in the `previous` test, but fail in this one.
+    pub fn regressions(&self, previous: &Entry) -> Vec<usize> {
+        self.samples
+            .iter()
+            .zip(&previous.samples)
+            .enumerate()
+            .filter(|(_, (now, before))| before.verdict == Verdict::Accepted && now.verdict != Verdict::Accepted)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn to_json(&self) -> JsonValue {
+        object! {
+            "time": self.time,
+            "source_hash": self.source_hash.clone(),
+            "points": self.points(),
+            "samples": self.samples.iter().map(|sample| object! {
+                "verdict": sample.verdict.to_string(),
+                "time_ms": sample.time_ms,
+                "memory_kb": sample.memory_kb,
+                "points": sample.points,
+            }).collect::<Vec<JsonValue>>(),
+        }
+    }
+
+    fn from_json(value: &JsonValue) -> Option<Self> {
+        let mut samples = Vec::new();
+        for sample in value["samples"].members() {
+            samples.push(SampleResult {
+                verdict: Verdict::parse(sample["verdict"].as_str()?)?,
+                time_ms: sample["time_ms"].as_u64().unwrap_or(0),
+                memory_kb: sample["memory_kb"].as_u64(),
+                points: sample["points"].as_u32().unwrap_or(0),
+            });
+        }
+        Some(Self {
+            time: value["time"].as_u64()?,
+            source_hash: value["source_hash"].as_str()?.to_string(),
+            samples,
+        })
+    }
+}
+
+/// The test history in a sample group directory.
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    pub fn open(dir: &Path) -> Self {
+        Self { dir: dir.to_path_buf() }
+    }
+
+    fn log_path(&self) -> PathBuf {
+        self.dir.join("history.jsonl")
+    }
+
+    fn snapshot_path(&self, source_hash: &str) -> PathBuf {
+        self.dir.join("history").join(source_hash)
+    }
+
+    /// Get the hash of a source, used to name its snapshot.
+    pub fn hash(source: &str) -> String {
+        hash::hex_hash(source.as_bytes())
+    }
+
+    /// Append a test to the history, and keep a snapshot of the tested source.
+    pub fn append(&self, entry: &Entry, source: &str) -> Result<(), Option<String>> {
+        let snapshot = self.snapshot_path(&entry.source_hash);
+        if !snapshot.exists() {
+            if let Err(err) = fs::create_dir_all(self.dir.join("history")).and_then(|_| fs::write(&snapshot, source)) {
+                return Err(Some(format!("Cannot write {}: {}", snapshot.display(), err)));
+            }
+        }
+        let path = self.log_path();
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| writeln!(file, "{}", entry.to_json().dump()));
+        match result {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Some(format!("Cannot write {}: {}", path.display(), err))),
+        }
+    }
+
+    /// Read the tests from the oldest. Broken lines are skipped.
+    pub fn load(&self) -> Vec<Entry> {
+        let content = fs::read_to_string(self.log_path()).unwrap_or_default();
+        content
+            .lines()
+            .filter_map(|line| json::parse(line).ok())
+            .filter_map(|value| Entry::from_json(&value))
+            .collect()
+    }
+
+    /// Read the snapshot of a tested source.
+    pub fn snapshot(&self, source_hash: &str) -> Option<String> {
+        fs::read_to_string(self.snapshot_path(source_hash)).ok()
+    }
+}
+
+/// Record a test of `target` in the sample group `dir`: append it to the history, warn about the samples which passed last time
+/// but fail now, and update the progress in the metadata of the problem.
+pub fn record_test(dir: &Path, target: &str, source_path: &Path, samples: Vec<SampleResult>) -> Result<(), Option<String>> {
+    if samples.is_empty() {
+        return Ok(());
+    }
+    let source = fs::read_to_string(source_path).unwrap_or_default();
+    let entry = Entry {
+        time: datetime::unix_now(),
+        source_hash: History::hash(&source),
+        samples,
+    };
+    let history = History::open(dir);
+    if let Some(previous) = history.load().last() {
+        let regressions = entry.regressions(previous);
+        if !regressions.is_empty() {
+            let tests = regressions.iter().map(|i| format!("#{i}")).collect::<Vec<String>>().join(", ");
+            eprintln!(
+                "{} Test {} passed last time, but fails now. See what changed with `oi_helper history {}`. ",
+                "[WARNING]".bold().yellow(),
+                tests,
+                target
+            );
+        }
+    }
+    history.append(&entry, &source)?;
+    let mut info = ProblemInfo::open(dir)?;
+    info.record_test(entry.passed(), entry.time);
+    info.save()
+}
@@ -0,0 +1,87 @@
+//! This file contains the `history` subcommand.
+
+use std::path::Path;
+
+use crossterm::style::Stylize;
+
+use super::{
+    history::{Entry, History, Verdict},
+    samples::Samples,
+    utils::{datetime, strdiff, table::Table},
+};
+
+/// How many unchanged lines are printed around the changes of a source.
+const DIFF_CONTEXT: usize = 3;
+
+/// Describe the samples which passed in `previous` but fail in `entry`, e.g. `#1 AC -> WA`.
+fn describe_regressions(entry: &Entry, previous: &Entry) -> String {
+    entry
+        .regressions(previous)
+        .iter()
+        .map(|i| format!("#{} {} -> {}", i, previous.samples[*i].verdict, entry.samples[*i].verdict))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Print the last `limit` tests of a target, and the source changes which made passed samples fail.
+pub fn history(target: &str, limit: usize) -> Result<(), Option<String>> {
+    let history = History::open(Path::new(&format!("./{}.smpd", Samples::group_of(target))));
+    let entries = history.load();
+    if entries.is_empty() {
+        println!("No tests of {} yet. ", target);
+        return Ok(());
+    }
+    let first = entries.len().saturating_sub(limit);
+
+    let mut table = Table::new(&["#", "Date", "Source", "Points", "Verdicts", "Max time", "Max memory", "Regressions"]);
+    for (i, entry) in entries.iter().enumerate().skip(first) {
+        let max_time = entry.samples.iter().map(|sample| sample.time_ms).max().unwrap_or(0);
+        let max_memory = entry.samples.iter().filter_map(|sample| sample.memory_kb).max();
+        let regressions = match i.checked_sub(1) {
+            Some(previous) => describe_regressions(entry, &entries[previous]),
+            None => String::new(),
+        };
+        table.push(vec![
+            (i + 1).to_string(),
+            datetime::format_datetime(entry.time),
+            entry.source_hash.chars().take(8).collect(),
+            entry.points().to_string(),
+            entry.samples.iter().map(|sample| sample.verdict.to_string()).collect::<Vec<String>>().join(" "),
+            format!("{}ms", max_time),
+            max_memory.map(|kb| format!("{:.1}MB", kb as f64 / 1024.0)).unwrap_or_else(|| String::from("-")),
+            regressions,
+        ]);
+    }
+    table.print();
+
+    // Show what changed in the source when samples started failing.
+    for i in first.max(1)..entries.len() {
+        let (previous, entry) = (&entries[i - 1], &entries[i]);
+        let regressions = describe_regressions(entry, previous);
+        if regressions.is_empty() {
+            continue;
+        }
+        println!();
+        println!("{}", format!("Regression in test {} ({}): {}", i + 1, datetime::format_datetime(entry.time), regressions).bold().red());
+        if previous.source_hash == entry.source_hash {
+            println!("The source didn't change since test {}, so the samples may fail by chance, e.g. near the time limit. ", i);
+            continue;
+        }
+        match (history.snapshot(&previous.source_hash), history.snapshot(&entry.source_hash)) {
+            (Some(before), Some(after)) => {
+                println!("{}", format!("Changes since test {}:", i).bold());
+                strdiff::print_line_diff(&strdiff::line_diff(&before, &after), DIFF_CONTEXT);
+            }
+            _ => println!("The source of test {} or {} isn't kept, so the changes cannot be shown. ", i, i + 1),
+        }
+    }
+
+    let last = &entries[entries.len() - 1];
+    let summary = format!("{} test(s). The last one got {} point(s). ", entries.len(), last.points());
+    if last.samples.iter().all(|sample| sample.verdict == Verdict::Accepted) {
+        println!("{}", summary.green());
+    } else {
+        println!("{}", summary.yellow());
+    }
+    Ok(())
+}
@@ -0,0 +1,199 @@
+//! This file contains the registry of supported languages, keyed by file extension.
+//!
+//! The commands are lists of arguments with placeholders:
+//! - `{source}`: the path to the source file;
+//! - `{output}`: the path to the build output without extension, in the build directory;
+//! - `{exe}`: the extension of executables on this platform, e.g. `.exe` on Windows;
+//! - `{class}`: the file name of the source without extension, which is the main class in Java;
+//! - `{cc_compiler}`: the `cc_compiler` in the configuration;
+//! - `{flags}`: the flags decided by [`Flags`], expanded into zero or more arguments.
+
+use std::path::Path;
+
+/// Which flags are passed to the compiler, besides the `flags=` directives in the source.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Flags {
+    /// `cc_flags` and the build profile's flags. The precompiled header is also used.
+    Workspace,
+    /// Only the build profile's flags.
+    Profile,
+    /// Only the directives.
+    Directives,
+}
+
+/// A supported language.
+pub struct Language {
+    pub name: &'static str,
+    /// The extensions of source files. The first one is used when creating sources.
+    pub extensions: &'static [&'static str],
+    /// The command compiling the source. Empty for interpreted languages.
+    pub compile: &'static [&'static str],
+    /// The file produced by the compiler, checked by the build cache.
+    pub output_file: &'static str,
+    /// The command running the program.
+    pub run: &'static [&'static str],
+    pub flags: Flags,
+    /// The prefix of line comments, where the directives are written.
+    pub comment: &'static str,
+    /// The template of `create`. C++ uses the templates in `resource.rs` instead.
+    pub template: &'static str,
+    /// The time limits of the samples are multiplied by this when testing, for slower languages.
+    pub time_multiplier: f64,
+}
+
+impl Language {
+    /// Check if this is C++, which has its own templates and supports the precompiled header.
+    pub fn is_cpp(&self) -> bool {
+        self.flags == Flags::Workspace
+    }
+
+    /// Check if the language needs compiling.
+    pub fn is_compiled(&self) -> bool {
+        !self.compile.is_empty()
+    }
+}
+
+const C_TEMPLATE: &str = r"// {##}
+// Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
+//
+
+#include <stdio.h>
+
+int main(void) {
+    return 0;
+}
+";
+
+const PYTHON_TEMPLATE: &str = r#"# {##}
+# Template generated by oi_helper (https://github.com/onion108/oi_helper)
+#
+
+import sys
+
+
+def main():
+    pass
+
+
+if __name__ == "__main__":
+    main()
+"#;
+
+const RUST_TEMPLATE: &str = r"// {##}
+// Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
+//
+
+use std::io::{self, Read};
+
+fn main() {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input).unwrap();
+}
+";
+
+const JAVA_TEMPLATE: &str = r"// {##}
+// Template generated by oi_helper (https://github.com/onion108/oi_helper)
+//
+
+import java.io.*;
+import java.util.*;
+
+public class {#class#} {
+    public static void main(String[] args) throws IOException {
+        BufferedReader in = new BufferedReader(new InputStreamReader(System.in));
+    }
+}
+";
+
+/// All the supported languages.
+pub static LANGUAGES: &[Language] = &[
+    Language {
+        name: "C++",
+        extensions: &["cpp", "cc", "cxx", "c++"],
+        compile: &["{cc_compiler}", "{flags}", "-o", "{output}{exe}", "{source}"],
+        output_file: "{output}{exe}",
+        run: &["{output}{exe}"],
+        flags: Flags::Workspace,
+        comment: "//",
+        template: "",
+        time_multiplier: 1.0,
+    },
+    Language {
+        name: "C",
+        extensions: &["c"],
+        compile: &["gcc", "-std=c11", "-O2", "{flags}", "-o", "{output}{exe}", "{source}", "-lm"],
+        output_file: "{output}{exe}",
+        run: &["{output}{exe}"],
+        flags: Flags::Profile,
+        comment: "//",
+        template: C_TEMPLATE,
+        time_multiplier: 1.0,
+    },
+    Language {
+        name: "Python",
+        extensions: &["py"],
+        compile: &[],
+        output_file: "",
+        run: &["python3", "{source}"],
+        flags: Flags::Directives,
+        comment: "#",
+        template: PYTHON_TEMPLATE,
+        time_multiplier: 3.0,
+    },
+    Language {
+        name: "Rust",
+        extensions: &["rs"],
+        compile: &["rustc", "--edition", "2021", "-O", "{flags}", "-o", "{output}{exe}", "{source}"],
+        output_file: "{output}{exe}",
+        run: &["{output}{exe}"],
+        flags: Flags::Directives,
+        comment: "//",
+        template: RUST_TEMPLATE,
+        time_multiplier: 1.0,
+    },
+    Language {
+        name: "Java",
+        extensions: &["java"],
+        compile: &["javac", "-encoding", "UTF-8", "{flags}", "-d", "{output}", "{source}"],
+        output_file: "{output}/{class}.class",
+        run: &["java", "-Xss512m", "-cp", "{output}", "{class}"],
+        flags: Flags::Directives,
+        comment: "//",
+        template: JAVA_TEMPLATE,
+        time_multiplier: 2.0,
+    },
+];
+
+/// Find the language of an extension.
+pub fn by_extension(extension: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|lang| lang.extensions.contains(&extension))
+}
+
+/// Find the language of a source file by its extension.
+pub fn by_path(path: &Path) -> Option<&'static Language> {
+    by_extension(&path.extension()?.to_string_lossy())
+}
+
+/// Get all the known extensions.
+pub fn extensions() -> Vec<&'static str> {
+    LANGUAGES.iter().flat_map(|lang| lang.extensions.iter().copied()).collect()
+}
+
+/// Replace the placeholders in a command. `{flags}` is replaced with `flags`, which may be any number of arguments.
+pub fn expand(command: &[&str], vars: &[(&str, String)], flags: &[String]) -> Vec<String> {
+    let mut result = Vec::new();
+    for arg in command {
+        if *arg == "{flags}" {
+            result.extend(flags.iter().cloned());
+            continue;
+        }
+        let mut arg = arg.to_string();
+        for (name, value) in vars {
+            arg = arg.replace(&format!("{{{name}}}"), value);
+        }
+        result.push(arg);
+    }
+    result
+}
@@ -0,0 +1,91 @@
+//! This file contains the precompiled `bits/stdc++.h` header, which makes compiling programs including it much faster.
+//!
+//! GCC looks for `bits/stdc++.h.gch` in the include paths before the header itself,
+//! so the header is precompiled into `target/pch/<hash>/bits/stdc++.h.gch` and the directory is added with `-I`.
+//! The hash covers the compiler, its version and the flags, since GCC only accepts a PCH built with the same flags.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crossterm::style::Stylize;
+
+use super::utils::hash::hex_hash;
+
+/// Check if the compiler supports the PCH. Only GCC is supported.
+pub fn supported(compiler: &str) -> bool {
+    let name = Path::new(compiler).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    name.contains("g++") || name.contains("gcc")
+}
+
+/// Get the directory of the PCH for the compiler and the flags.
+fn pch_dir(target_dir: &Path, compiler: &str, args: &[String]) -> PathBuf {
+    let version = Command::new(compiler)
+        .arg("-dumpversion")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    let mut data = format!("{compiler}\0{version}");
+    for arg in args {
+        data.push('\0');
+        data.push_str(arg);
+    }
+    target_dir.join("pch").join(hex_hash(data.as_bytes()))
+}
+
+/// Get the path of the precompiled header in the directory.
+fn gch_path(dir: &Path) -> PathBuf {
+    dir.join("bits").join("stdc++.h.gch")
+}
+
+/// Find the directory of a PCH built with the compiler and the flags, which should be added with `-I`.
+pub fn find(target_dir: &Path, compiler: &str, args: &[String]) -> Option<PathBuf> {
+    if !supported(compiler) {
+        return None;
+    }
+    let dir = pch_dir(target_dir, compiler, args);
+    if gch_path(&dir).is_file() {
+        Some(dir)
+    } else {
+        None
+    }
+}
+
+/// Precompile `bits/stdc++.h` with the compiler and the flags.
+pub fn build(target_dir: &Path, compiler: &str, args: &[String]) -> Result<PathBuf, Option<String>> {
+    if !supported(compiler) {
+        return Err(Some(format!("Precompiled headers are only supported with GCC, but the compiler is `{compiler}`. ")));
+    }
+    let dir = pch_dir(target_dir, compiler, args);
+    let gch = gch_path(&dir);
+    if let Err(err) = fs::create_dir_all(dir.join("bits")) {
+        return Err(Some(format!("Cannot create {}: {}", dir.display(), err)));
+    }
+
+    // The wrapper is outside `bits/`, so it never includes itself.
+    let wrapper = dir.join("stdc++.pch.h");
+    if let Err(err) = fs::write(&wrapper, "#include <bits/stdc++.h>\n") {
+        return Err(Some(format!("Cannot create {}: {}", wrapper.display(), err)));
+    }
+    // Remove the old one first, so a failed build doesn't leave a stale PCH.
+    let _ = fs::remove_file(&gch);
+
+    eprintln!("Precompiling bits/stdc++.h, this may take a while... ");
+    match Command::new(compiler)
+        .args(args)
+        .arg("-x")
+        .arg("c++-header")
+        .arg(&wrapper)
+        .arg("-o")
+        .arg(&gch)
+        .status()
+    {
+        Ok(status) if status.success() => {
+            println!("{}", format!("Precompiled header saved to {}", gch.display()).green());
+            Ok(dir)
+        }
+        _ => Err(Some("Failed to precompile bits/stdc++.h. ".to_string())),
+    }
+}
@@ -0,0 +1,170 @@
+//! This file contains the metadata of a problem, saved as `problem_info.json` in its sample group:
+//! where it comes from, what it's about, and how solving it is going.
+
+use std::{
+    fmt,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use json::{object, JsonValue};
+
+use super::utils::datetime;
+
+/// The keys which can be set with `problem set`.
+pub static KEYS: &[&str] = &["oj", "problem_id", "url", "title", "tags", "difficulty", "status", "attempts", "notes"];
+
+/// The keys filled in by `test`, which are kept when the problem is fetched again.
+const PROGRESS_KEYS: &[&str] = &["status", "attempts", "notes", "last_attempt", "solved_at"];
+
+/// How solving a problem is going.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Not tested yet.
+    Todo,
+    /// The last test failed.
+    Wrong,
+    /// The last test passed.
+    Accepted,
+}
+
+impl Status {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "todo" => Some(Self::Todo),
+            "wa" => Some(Self::Wrong),
+            "ac" => Some(Self::Accepted),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Todo => write!(f, "todo"),
+            Self::Wrong => write!(f, "WA"),
+            Self::Accepted => write!(f, "AC"),
+        }
+    }
+}
+
+/// The metadata of a problem. Unknown keys in the file are kept as they are.
+pub struct ProblemInfo {
+    path: PathBuf,
+    data: JsonValue,
+}
+
+impl ProblemInfo {
+    /// Read the metadata in a sample group directory, or start with empty metadata if there isn't any.
+    pub fn open(dir: &Path) -> Result<Self, Option<String>> {
+        let path = dir.join("problem_info.json");
+        let data = match fs::read_to_string(&path) {
+            Ok(content) => match json::parse(&content) {
+                Ok(data) if data.is_object() => data,
+                _ => return Err(Some(format!("{} is broken. Please check it or remove it. ", path.display()))),
+            },
+            Err(_) => object! {},
+        };
+        Ok(Self { path, data })
+    }
+
+    /// Read the metadata of the sample group `name`, e.g. `a` for `a.smpd`.
+    pub fn of_group(name: &str) -> Result<Self, Option<String>> {
+        Self::open(Path::new(&format!("./{}.smpd", name)))
+    }
+
+    /// Check if the metadata has been saved.
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub fn save(&self) -> Result<(), Option<String>> {
+        if let Some(dir) = self.path.parent() {
+            if let Err(err) = fs::create_dir_all(dir) {
+                return Err(Some(format!("Cannot create {}: {err}", dir.display())));
+            }
+        }
+        match fs::write(&self.path, self.data.pretty(4)) {
+            Ok(_) => Ok(()),
+            Err(err) => Err(Some(format!("Cannot write to {}: {err}", self.path.display()))),
+        }
+    }
+
+    /// Get a string value, or an empty string if it isn't set.
+    pub fn get_str(&self, key: &str) -> String {
+        match &self.data[key] {
+            JsonValue::Null => String::new(),
+            value => value.as_str().map(String::from).unwrap_or_else(|| value.dump()),
+        }
+    }
+
+    pub fn status(&self) -> Status {
+        self.data["status"].as_str().and_then(Status::parse).unwrap_or(Status::Todo)
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.data["attempts"].as_u32().unwrap_or(0)
+    }
+
+    pub fn tags(&self) -> Vec<String> {
+        self.data["tags"].members().filter_map(|tag| tag.as_str()).map(String::from).collect()
+    }
+
+    /// Set a key listed in [`KEYS`]. An empty value removes the key.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        if !KEYS.contains(&key) {
+            return Err(format!("Unknown key `{key}`. Available keys: {}", KEYS.join(", ")));
+        }
+        if value.is_empty() {
+            self.data.remove(key);
+            return Ok(());
+        }
+        self.data[key] = match key {
+            "tags" => value.split(',').map(str::trim).filter(|tag| !tag.is_empty()).collect::<Vec<&str>>().into(),
+            "attempts" => match value.parse::<u32>() {
+                Ok(attempts) => attempts.into(),
+                Err(_) => return Err(format!("`attempts` should be a number, but got `{value}`. ")),
+            },
+            "status" => match Status::parse(value) {
+                Some(status) => status.to_string().into(),
+                None => return Err(format!("`status` should be todo, WA or AC, but got `{value}`. ")),
+            },
+            _ => value.into(),
+        };
+        Ok(())
+    }
+
+    /// Fill in the metadata of a fetched problem, keeping the progress.
+    pub fn merge(&mut self, metadata: &JsonValue) {
+        for (key, value) in metadata.entries() {
+            if !PROGRESS_KEYS.contains(&key) && !value.is_null() {
+                self.data[key] = value.clone();
+            }
+        }
+    }
+
+    /// Record a test of the problem at `time`, in seconds since the Unix epoch.
+    pub fn record_test(&mut self, passed: bool, time: u64) {
+        self.data["attempts"] = (self.attempts() + 1).into();
+        self.data["last_attempt"] = time.into();
+        if passed {
+            self.data["status"] = Status::Accepted.to_string().into();
+            if self.data["solved_at"].is_null() {
+                self.data["solved_at"] = time.into();
+            }
+        } else {
+            self.data["status"] = Status::Wrong.to_string().into();
+        }
+    }
+
+    /// Get a timestamp value, like `last_attempt`, in seconds since the Unix epoch.
+    pub fn timestamp(&self, key: &str) -> Option<u64> {
+        self.data[key].as_u64()
+    }
+
+    /// Format a timestamp value, like `last_attempt`, as a date.
+    pub fn date(&self, key: &str) -> Option<String> {
+        self.timestamp(key).map(datetime::format_date)
+    }
+}
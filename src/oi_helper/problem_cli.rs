@@ -0,0 +1,68 @@
+//! This file contains the `problem` subcommand.
+
+use crossterm::style::Stylize;
+
+use crate::ProblemSubcommand;
+
+use super::{problem::ProblemInfo, samples::Samples};
+
+/// The fields printed by `problem show`, in order, with their labels.
+const FIELDS: &[(&str, &str)] = &[
+    ("oj", "OJ"),
+    ("problem_id", "Problem"),
+    ("url", "URL"),
+    ("difficulty", "Difficulty"),
+    ("tags", "Tags"),
+    ("time_limit", "Time limit (ms)"),
+    ("memory_limit", "Memory limit (MB)"),
+];
+
+/// Manage the metadata of problems.
+pub fn problem(subcommand: &ProblemSubcommand) -> Result<(), Option<String>> {
+    match subcommand {
+        ProblemSubcommand::Set { target, key, value } => {
+            let mut info = ProblemInfo::of_group(&Samples::group_of(target))?;
+            info.set(key, value)?;
+            info.save()?;
+            if value.is_empty() {
+                println!("Removed {} of {}. ", key, target);
+            } else if key == "tags" {
+                println!("Set tags of {} to {}. ", target, info.tags().join(", "));
+            } else {
+                println!("Set {} of {} to {}. ", key, target, info.get_str(key));
+            }
+        }
+
+        ProblemSubcommand::Show { target } => {
+            let info = ProblemInfo::of_group(&Samples::group_of(target))?;
+            if !info.exists() {
+                println!("No metadata of {} yet. Fetch the problem, or use `oi_helper problem set`. ", target);
+                return Ok(());
+            }
+            let title = info.get_str("title");
+            println!("{}", if title.is_empty() { target.clone() } else { title }.bold());
+            for (key, label) in FIELDS {
+                let value = match *key {
+                    "tags" => info.tags().join(", "),
+                    _ => info.get_str(key),
+                };
+                if !value.is_empty() {
+                    println!("{}: {}", label.dark_grey(), value);
+                }
+            }
+            let mut status = format!("{} ({} attempt(s))", info.status(), info.attempts());
+            if let Some(date) = info.date("last_attempt") {
+                status += &format!(", last tested on {date}");
+            }
+            if let Some(date) = info.date("solved_at") {
+                status += &format!(", solved on {date}");
+            }
+            println!("{}: {}", "Status".dark_grey(), status);
+            let notes = info.get_str("notes");
+            if !notes.is_empty() {
+                println!("{}: {}", "Notes".dark_grey(), notes);
+            }
+        }
+    }
+    Ok(())
+}
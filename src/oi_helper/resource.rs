@@ -2,7 +2,7 @@
 
 
 /// DEBUG Kits
-pub static CPP_TEMPLATE_DEBUG_KIT: &'static str = r"
+pub static CPP_TEMPLATE_DEBUG_KIT: &str = r"
 
 /* Debug Kit Start */
 
@@ -17,12 +17,16 @@ pub static CPP_TEMPLATE_DEBUG_KIT: &'static str = r"
 ";
 
 /// The default C++ template.
-pub static CPP_TEMPLATE_0: &'static str = r"
+pub static CPP_TEMPLATE_0: &str = r#"
 // {##}
 // Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
 //
 
 #include <iostream>
+{#if file_io#}
+#include <cstdio>
+{#end#}
 #include <algorithm>
 #include <vector>
 #include <cmath>
@@ -34,18 +38,26 @@ using namespace std;
 static const int MAXN = {#maxn_value#};
 
 int main() {
+{#if file_io#}
+    freopen("{#file_io#}.in", "r", stdin);
+    freopen("{#file_io#}.out", "w", stdout);
+{#end#}
     return 0;
 }
 
-";
+"#;
 
 /// An alternative template.
-pub static CPP_TEMPLATE_1: &'static str = r"
+pub static CPP_TEMPLATE_1: &str = r#"
 // {##}
 // Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
 //
 
 #include <iostream>
+{#if file_io#}
+#include <cstdio>
+{#end#}
 #include <algorithm>
 
 using std::cin;
@@ -56,17 +68,25 @@ using std::endl;
 static const int MAXN = {#maxn_value#};
 
 int main() {
+{#if file_io#}
+    freopen("{#file_io#}.in", "r", stdin);
+    freopen("{#file_io#}.out", "w", stdout);
+{#end#}
 }
 
-";
+"#;
 
 /// Default dp template.
-pub static CPP_DP_TEMPLATE_0: &'static str = r"
+pub static CPP_DP_TEMPLATE_0: &str = r#"
 // {##}
 // Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
 //
 
 #include <iostream>
+{#if file_io#}
+#include <cstdio>
+{#end#}
 #include <algorithm>
 #include <vector>
 #include <cmath>
@@ -79,18 +99,26 @@ static const int MAXN = {#maxn_value#};
 int dp[MAXN];
 
 int main() {
+{#if file_io#}
+    freopen("{#file_io#}.in", "r", stdin);
+    freopen("{#file_io#}.out", "w", stdout);
+{#end#}
     return 0;
 }
 
-";
+"#;
 
 /// An alternative template.
-pub static CPP_DP_TEMPLATE_1: &'static str = r"
+pub static CPP_DP_TEMPLATE_1: &str = r#"
 // {##}
 // Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
 //
 
 #include <iostream>
+{#if file_io#}
+#include <cstdio>
+{#end#}
 #include <algorithm>
 
 using std::cin;
@@ -102,17 +130,25 @@ static const int MAXN = {#maxn_value#};
 int dp[MAXN];
 
 int main() {
+{#if file_io#}
+    freopen("{#file_io#}.in", "r", stdin);
+    freopen("{#file_io#}.out", "w", stdout);
+{#end#}
 }
 
-";
+"#;
 
 /// Default dp-2d template.
-pub static CPP_DP_2D_TEMPLATE_0: &'static str = r"
+pub static CPP_DP_2D_TEMPLATE_0: &str = r#"
 // {##}
 // Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
 //
 
 #include <iostream>
+{#if file_io#}
+#include <cstdio>
+{#end#}
 #include <algorithm>
 #include <vector>
 #include <cmath>
@@ -126,18 +162,26 @@ static const int MAXL = {#maxl_value#};
 int dp[MAXN][MAXL];
 
 int main() {
+{#if file_io#}
+    freopen("{#file_io#}.in", "r", stdin);
+    freopen("{#file_io#}.out", "w", stdout);
+{#end#}
     return 0;
 }
 
-";
+"#;
 
 /// An alternative template.
-pub static CPP_DP_2D_TEMPLATE_1: &'static str = r"
+pub static CPP_DP_2D_TEMPLATE_1: &str = r#"
 // {##}
 // Template generated by oi_helper (https://github.com/onion108/oi_helper)
+// oi_helper: stack=512M
 //
 
 #include <iostream>
+{#if file_io#}
+#include <cstdio>
+{#end#}
 #include <algorithm>
 
 using std::cin;
@@ -150,6 +194,249 @@ static const int MAXL = {#maxl_value#};
 int dp[MAXN][MAXL];
 
 int main() {
+{#if file_io#}
+    freopen("{#file_io#}.in", "r", stdin);
+    freopen("{#file_io#}.out", "w", stdout);
+{#end#}
+}
+
+"#;
+/// Snippet: fast input and output of integers.
+pub static SNIPPET_FAST_IO: &str = r"
+// description: Fast input and output of integers with getchar and putchar.
+#include <cstdio>
+
+template <typename T = long long>
+inline T read() {
+    T x = 0;
+    bool negative = false;
+    int c = getchar();
+    while (c < '0' || c > '9') {
+        if (c == '-') negative = true;
+        c = getchar();
+    }
+    while (c >= '0' && c <= '9') {
+        x = x * 10 + (c - '0');
+        c = getchar();
+    }
+    return negative ? -x : x;
+}
+
+template <typename T>
+inline void write(T x) {
+    if (x < 0) {
+        putchar('-');
+        x = -x;
+    }
+    if (x > 9) write(x / 10);
+    putchar('0' + x % 10);
 }
+";
+
+/// Snippet: integers modulo a prime.
+pub static SNIPPET_MODINT: &str = r"
+// description: Integers modulo a prime, with inverses by Fermat's little theorem.
+template <int MOD>
+struct ModInt {
+    int v;
+    ModInt(long long x = 0) {
+        x %= MOD;
+        if (x < 0) x += MOD;
+        v = (int)x;
+    }
+    ModInt &operator+=(const ModInt &o) { v += o.v; if (v >= MOD) v -= MOD; return *this; }
+    ModInt &operator-=(const ModInt &o) { v -= o.v; if (v < 0) v += MOD; return *this; }
+    ModInt &operator*=(const ModInt &o) { v = (int)(1LL * v * o.v % MOD); return *this; }
+    ModInt &operator/=(const ModInt &o) { return *this *= o.inv(); }
+    friend ModInt operator+(ModInt a, const ModInt &b) { return a += b; }
+    friend ModInt operator-(ModInt a, const ModInt &b) { return a -= b; }
+    friend ModInt operator*(ModInt a, const ModInt &b) { return a *= b; }
+    friend ModInt operator/(ModInt a, const ModInt &b) { return a /= b; }
+    friend bool operator==(const ModInt &a, const ModInt &b) { return a.v == b.v; }
+    friend bool operator!=(const ModInt &a, const ModInt &b) { return a.v != b.v; }
+    ModInt pow(long long e) const {
+        ModInt result = 1, base = *this;
+        for (; e > 0; e >>= 1, base *= base)
+            if (e & 1) result *= base;
+        return result;
+    }
+    ModInt inv() const { return pow(MOD - 2); }
+};
+
+using mint = ModInt<998244353>;
+";
+
+/// Snippet: disjoint set union.
+pub static SNIPPET_DSU: &str = r"
+// description: Disjoint set union with path compression and union by size.
+#include <vector>
+#include <numeric>
+#include <utility>
+
+struct DSU {
+    std::vector<int> parent, size;
+    explicit DSU(int n) : parent(n), size(n, 1) { std::iota(parent.begin(), parent.end(), 0); }
+    int find(int x) {
+        while (parent[x] != x) x = parent[x] = parent[parent[x]];
+        return x;
+    }
+    bool unite(int a, int b) {
+        a = find(a), b = find(b);
+        if (a == b) return false;
+        if (size[a] < size[b]) std::swap(a, b);
+        parent[b] = a;
+        size[a] += size[b];
+        return true;
+    }
+    bool same(int a, int b) { return find(a) == find(b); }
+};
+";
+
+/// Snippet: segment tree.
+pub static SNIPPET_SEGTREE: &str = r"
+// description: Segment tree with point updates and range queries of a monoid.
+#include <vector>
+#include <functional>
+
+template <typename T>
+struct SegTree {
+    int n;
+    std::vector<T> tree;
+    T identity;
+    std::function<T(const T &, const T &)> op;
+    SegTree(int n, T identity, std::function<T(const T &, const T &)> op)
+        : n(n), tree(2 * n, identity), identity(identity), op(op) {}
+    // Set the value at `pos`, 0-indexed.
+    void set(int pos, T value) {
+        for (tree[pos += n] = value; pos > 1; pos >>= 1)
+            tree[pos >> 1] = pos & 1 ? op(tree[pos ^ 1], tree[pos]) : op(tree[pos], tree[pos ^ 1]);
+    }
+    // Query [l, r), 0-indexed.
+    T query(int l, int r) const {
+        T left = identity, right = identity;
+        for (l += n, r += n; l < r; l >>= 1, r >>= 1) {
+            if (l & 1) left = op(left, tree[l++]);
+            if (r & 1) right = op(tree[--r], right);
+        }
+        return op(left, right);
+    }
+};
+";
+
+/// Snippet: segment tree with lazy propagation.
+pub static SNIPPET_LAZY_SEGTREE: &str = r"
+// description: Segment tree with range additions and range sums, reading the initial values with fast_io.
+// requires: fast_io
+#include <vector>
+
+struct LazySegTree {
+    int n;
+    std::vector<long long> sum, add;
+    explicit LazySegTree(int n) : n(n), sum(4 * n), add(4 * n) {}
+    // Read `n` values from the input, 1-indexed.
+    void read_values() { build(1, 1, n); }
+    void build(int node, int l, int r) {
+        if (l == r) {
+            sum[node] = read<long long>();
+            return;
+        }
+        int mid = (l + r) / 2;
+        build(node * 2, l, mid);
+        build(node * 2 + 1, mid + 1, r);
+        sum[node] = sum[node * 2] + sum[node * 2 + 1];
+    }
+    void apply(int node, int l, int r, long long value) {
+        sum[node] += value * (r - l + 1);
+        add[node] += value;
+    }
+    void push(int node, int l, int r) {
+        if (add[node] == 0) return;
+        int mid = (l + r) / 2;
+        apply(node * 2, l, mid, add[node]);
+        apply(node * 2 + 1, mid + 1, r, add[node]);
+        add[node] = 0;
+    }
+    // Add `value` to [ql, qr], 1-indexed.
+    void update(int ql, int qr, long long value, int node = 1, int l = 1, int r = -1) {
+        if (r == -1) r = n;
+        if (qr < l || r < ql) return;
+        if (ql <= l && r <= qr) {
+            apply(node, l, r, value);
+            return;
+        }
+        push(node, l, r);
+        int mid = (l + r) / 2;
+        update(ql, qr, value, node * 2, l, mid);
+        update(ql, qr, value, node * 2 + 1, mid + 1, r);
+        sum[node] = sum[node * 2] + sum[node * 2 + 1];
+    }
+    // Sum of [ql, qr], 1-indexed.
+    long long query(int ql, int qr, int node = 1, int l = 1, int r = -1) {
+        if (r == -1) r = n;
+        if (qr < l || r < ql) return 0;
+        if (ql <= l && r <= qr) return sum[node];
+        push(node, l, r);
+        int mid = (l + r) / 2;
+        return query(ql, qr, node * 2, l, mid) + query(ql, qr, node * 2 + 1, mid + 1, r);
+    }
+};
+";
+
+/// Snippet: weighted graph in adjacency lists.
+pub static SNIPPET_GRAPH: &str = r"
+// description: Weighted directed graph stored in adjacency lists.
+#include <vector>
+
+struct Graph {
+    struct Edge {
+        int to;
+        long long weight;
+    };
+    std::vector<std::vector<Edge>> adj;
+    explicit Graph(int n) : adj(n) {}
+    void add_edge(int from, int to, long long weight = 1) { adj[from].push_back({to, weight}); }
+};
+";
+
+/// Snippet: Dijkstra's algorithm.
+pub static SNIPPET_DIJKSTRA: &str = r"
+// description: Shortest paths from a source with non-negative weights, in O((n + m) log m).
+// requires: graph
+#include <vector>
+#include <queue>
+#include <limits>
+#include <functional>
+#include <utility>
+
+std::vector<long long> dijkstra(const Graph &g, int source) {
+    const long long INF = std::numeric_limits<long long>::max();
+    std::vector<long long> dist(g.adj.size(), INF);
+    std::priority_queue<std::pair<long long, int>, std::vector<std::pair<long long, int>>, std::greater<std::pair<long long, int>>> heap;
+    dist[source] = 0;
+    heap.push({0, source});
+    while (!heap.empty()) {
+        long long d = heap.top().first;
+        int u = heap.top().second;
+        heap.pop();
+        if (d != dist[u]) continue;
+        for (const auto &e : g.adj[u]) {
+            if (d + e.weight < dist[e.to]) {
+                dist[e.to] = d + e.weight;
+                heap.push({dist[e.to], e.to});
+            }
+        }
+    }
+    return dist;
+}
+";
 
-";
\ No newline at end of file
+/// The built-in snippets.
+pub static SNIPPETS: &[(&str, &str)] = &[
+    ("fast_io", SNIPPET_FAST_IO),
+    ("modint", SNIPPET_MODINT),
+    ("dsu", SNIPPET_DSU),
+    ("segtree", SNIPPET_SEGTREE),
+    ("lazy_segtree", SNIPPET_LAZY_SEGTREE),
+    ("graph", SNIPPET_GRAPH),
+    ("dijkstra", SNIPPET_DIJKSTRA),
+];
@@ -1,10 +1,10 @@
 //! This file contains functions and data types for processing samples.
 
-use std::{path::{Path, PathBuf}, fs::{File, OpenOptions}, io::{Read, Write}};
+use std::{path::{Path, PathBuf}, fs::{self, File, OpenOptions}, io::{Read, Write}};
 
 use json::{JsonValue, object};
 
-use super::utils::web::{get_luogu_problem_content, get_test_case_from_luogu_tree};
+use super::{languages, problem::ProblemInfo, utils::web::{luogu, FetchedProblem}};
 
 pub struct Samples {
     config: JsonValue,
@@ -55,6 +55,25 @@ impl Samples {
 
     }
 
+    /// Open the sample group `name` (i.e. `./name.smpd`), creating the directory and its configuration if they don't exist yet.
+    pub fn open_group(name: &str) -> Result<Self, Option<String>> {
+        let path_to_sampledir_str = format!("./{}.smpd", name);
+        let path_to_sampledir = Path::new(&path_to_sampledir_str);
+        if path_to_sampledir.exists() && !path_to_sampledir.is_dir() {
+            return Err(Some(format!("Cannot create the sample because the filename {} has been used. Please check your directory.", path_to_sampledir_str)))
+        }
+        if !path_to_sampledir.exists() {
+            if let Err(err) = fs::create_dir_all(path_to_sampledir) {
+                return Err(Some(format!("Cannot create the sample: {err} ")));
+            }
+        }
+        let config_path = path_to_sampledir.join("samples_info.json");
+        if !config_path.as_path().exists() {
+            Self::create(config_path.to_str().unwrap())?;
+        }
+        Self::from_file(config_path.to_str().unwrap())
+    }
+
     fn get_default_config() -> JsonValue {
         object! {
             "sample_list": []
@@ -112,6 +131,32 @@ impl Samples {
         Ok(())
     }
 
+    /// Get the name of the files if the tests read `NAME.in` and write `NAME.out`, or `None` if they use stdin and stdout.
+    pub fn file_io(&self) -> Option<String> {
+        self.config["file_io"].as_str().map(String::from)
+    }
+
+    /// Set the name of the input and output files, or use stdin and stdout with `None`.
+    pub fn set_file_io(&mut self, name: Option<&str>) -> Result<(), Option<String>> {
+        match name {
+            Some(name) => self.config["file_io"] = name.into(),
+            None => {
+                self.config.remove("file_io");
+            }
+        }
+        self.save()
+    }
+
+    /// The number of samples in the group.
+    pub fn len(&self) -> usize {
+        self.config["sample_list"].len()
+    }
+
+    /// Check if the group has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Create a sample.
     pub fn create_sample(&mut self, points: u32, timeout: u32, mem_limit: u32) -> Result<i32, Option<String>> {
         self.check_config()?;
@@ -216,62 +261,93 @@ impl Samples {
         }))
     }
 
+    /// Create a sample and fill in its input and output.
+    pub fn add_sample(&mut self, points: u32, timeout: u32, mem_limit: u32, input: &str, output: &str) -> Result<i32, Option<String>> {
+        let number = self.create_sample(points, timeout, mem_limit)?;
+
+        let in_path = Path::new(&self.config_file_path).parent().unwrap().join(format!("{}.in", number));
+        let out_path = Path::new(&self.config_file_path).parent().unwrap().join(format!("{}.out", number));
+
+        let mut in_file = match OpenOptions::new().write(true).truncate(true).open(&in_path) {
+            Ok(f) => f,
+            Err(err) => {
+                return Err(Some(format!("Error while opening sample from {}: {}", in_path.to_str().unwrap_or("undefined"), err)));
+            }
+        };
+
+        let mut out_file = match OpenOptions::new().write(true).truncate(true).open(&out_path) {
+            Ok(f) => f,
+            Err(err) => {
+                return Err(Some(format!("Error while opening sample from {}: {}", out_path.to_str().unwrap_or("undefined"), err)));
+            }
+        };
+
+        match write!(&mut in_file, "{}", input) {
+            Ok(_) => {}
+            Err(err) => {
+                return Err(Some(format!("Error while writing sample: {}", err)));
+            }
+        }
+
+        match write!(&mut out_file, "{}", output) {
+            Ok(_) => {}
+            Err(err) => {
+                return Err(Some(format!("Error while writing sample: {}", err)));
+            }
+        }
+
+        Ok(number)
+    }
+
     /// Load the samples from Luogu with a specified problem id
     pub fn load_sample_from_luogu(&mut self, problem_id: &str) -> Result<(), Option<String>> {
 
         eprintln!("Starting fetching samples from {}... ", problem_id);
 
-        let samples = get_test_case_from_luogu_tree(&(match get_luogu_problem_content(problem_id) {
+        let problem = match luogu::get_problem(problem_id) {
             Ok(o) => o,
             Err(err) => return Err(Some(format!("Error occured while fetching samples: {}", err)))
-        }));
-        let each_point = 100 / (samples.len() as u32);
-
-        eprintln!("Content fetched. Loading {} sample(s)... ", samples.len());
-
-        for case in samples {
-
-            let number = self.create_sample(each_point, 1000, 256)?;
-            eprintln!("Loading sample #{number}... ");
+        };
 
-            let in_path = Path::new(&self.config_file_path).parent().unwrap().join(&format!("{}.in", number));
-            let out_path = Path::new(&self.config_file_path).parent().unwrap().join(&format!("{}.out", number));
+        self.load_problem(&problem)?;
 
-            let mut in_file = match OpenOptions::new().write(true).truncate(true).open(&in_path) {
-                Ok(f) => f,
-                Err(err) => {
-                    return Err(Some(format!("Error while opening sample from {}: {}", in_path.to_str().unwrap_or("undefined"), err)));
-                }
-            };
+        eprintln!("Fetching done. ");
+        Ok(())
 
-            let mut out_file = match OpenOptions::new().write(true).truncate(true).open(&out_path) {
-                Ok(f) => f,
-                Err(err) => {
-                    return Err(Some(format!("Error while opening sample from {}: {}", out_path.to_str().unwrap_or("undefined"), err)));
-                }
-            };
+    }
 
-            match write!(&mut in_file, "{}", case.0) {
-                Ok(_) => {}
-                Err(err) => {
-                    return Err(Some(format!("Error while writing sample: {}", err)));
-                }
-            }
+    /// Load the samples of a fetched problem, and save its metadata into `problem_info.json`.
+    pub fn load_problem(&mut self, problem: &FetchedProblem) -> Result<(), Option<String>> {
+        if problem.samples.is_empty() {
+            return Err(Some(format!("No samples found in problem {}. ", problem.problem_id)));
+        }
+        let each_point = 100 / (problem.samples.len() as u32);
 
-            match write!(&mut out_file, "{}", case.1) {
-                Ok(_) => {}
-                Err(err) => {
-                    return Err(Some(format!("Error while writing sample: {}", err)));
-                }
-            }
+        eprintln!("Content fetched. Loading {} sample(s) (time limit: {}ms, memory limit: {}MB)... ", problem.samples.len(), problem.time_limit, problem.memory_limit);
 
+        for case in &problem.samples {
+            let number = self.add_sample(each_point, problem.time_limit, problem.memory_limit, &case.0, &case.1)?;
             eprintln!("Loaded sample #{number}. ");
-
         }
 
-        eprintln!("Fetching done. ");
-        Ok(())
+        let mut info = ProblemInfo::open(Path::new(&self.config_file_path).parent().unwrap())?;
+        info.merge(&problem.metadata());
+        info.save()
+    }
+
+    /// Get the name of the sample group of a source, e.g. `a` for both `a` and `a.py`.
+    pub fn group_of(target: &str) -> String {
+        let path = Path::new(target);
+        match languages::by_path(path) {
+            Some(_) => path.with_extension("").to_string_lossy().to_string(),
+            None => target.to_owned(),
+        }
+    }
 
+    /// Read the name of the input and output files of the sample group `name`, if it exists and uses file IO.
+    pub fn group_file_io(name: &str) -> Option<String> {
+        let content = fs::read_to_string(Path::new(&format!("./{}.smpd", name)).join("samples_info.json")).ok()?;
+        json::parse(&content).ok()?["file_io"].as_str().map(String::from)
     }
 
 }
@@ -7,7 +7,7 @@ use super::{workspace::Workspace, samples::Samples};
 
 
 
-pub fn samples(_workspace: &mut Workspace, subcommand: &SamplesSubcommand) -> Result<(), Option<String>> {
+pub fn samples(workspace: &mut Workspace, subcommand: &SamplesSubcommand) -> Result<(), Option<String>> {
     
     match subcommand {
         SamplesSubcommand::Init { name } => {
@@ -34,32 +34,29 @@ pub fn samples(_workspace: &mut Workspace, subcommand: &SamplesSubcommand) -> Re
             let path_to_sampledir_str = format!("./{}.smpd", name.to_owned());
             let path_to_sampledir = Path::new(&path_to_sampledir_str);
             let mut samples = Samples::from_file(path_to_sampledir.join("samples_info.json").to_str().unwrap())?;
-            samples.create_sample(*points, *timeout, *memory_limit)?;
+            let timeout = timeout.unwrap_or(workspace.config().sample_timeout);
+            let memory_limit = memory_limit.unwrap_or(workspace.config().sample_memory_limit);
+            samples.create_sample(*points, timeout, memory_limit)?;
         }
 
         SamplesSubcommand::Lgfetch { name, problem_id } => {
-            
-            let path_to_sampledir_str = format!("./{}.smpd", name.to_owned());
-            let path_to_sampledir = Path::new(&path_to_sampledir_str);
-            if path_to_sampledir.exists() && !path_to_sampledir.is_dir() {
-                return Err(Some(format!("Cannot create the sample because the filename {} has been used. Please check your directory.", path_to_sampledir_str)))
-            }
-            if !path_to_sampledir.exists() {
-                match fs::create_dir(path_to_sampledir) {
-                    Ok(_) => {}
-                    Err(err) => {
-                        return Err(Some(format!("Cannot create the sample: {err} ")))
-                    }
-                }
-            }
-            let config_path = path_to_sampledir.join("samples_info.json");
-            if !config_path.as_path().exists() {
-                Samples::create(config_path.to_str().unwrap())?;
-            }
-
-            let mut samples = Samples::from_file(config_path.to_str().unwrap())?;
+            let mut samples = Samples::open_group(name)?;
             samples.load_sample_from_luogu(problem_id)?;
+        }
 
+        SamplesSubcommand::FileIo { name, file_name, off } => {
+            let mut samples = Samples::open_group(name)?;
+            if *off {
+                samples.set_file_io(None)?;
+                println!("The tests of {} use stdin and stdout. ", name);
+            } else {
+                let file_name = file_name.as_deref().unwrap_or(name);
+                if file_name.is_empty() || file_name.contains(['/', '\\']) {
+                    return Err(Some(format!("Invalid file name `{file_name}`. ")));
+                }
+                samples.set_file_io(Some(file_name))?;
+                println!("The tests of {} read {}.in and write {}.out. ", name, file_name, file_name);
+            }
         }
     }
     Ok(())
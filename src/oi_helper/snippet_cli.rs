@@ -0,0 +1,74 @@
+//! This file contains the `snippet` subcommand.
+
+use std::{fs, path::Path};
+
+use crossterm::style::Stylize;
+
+use crate::SnippetSubcommand;
+
+use super::{
+    languages, resource,
+    snippets::{self, Snippet},
+    templates::Registry,
+    workspace::Workspace,
+};
+
+/// Manage the snippets. Source names are resolved in the workspace, if any.
+pub fn snippet(registry: &Registry, workspace: Option<&Workspace>, subcommand: &SnippetSubcommand) -> Result<(), Option<String>> {
+    match subcommand {
+        SnippetSubcommand::Add { file, snippets: names } => {
+            let file = match workspace {
+                Some(workspace) => workspace.source_name(file),
+                None => file.clone(),
+            };
+            if !languages::by_path(Path::new(&file)).is_some_and(|language| language.is_cpp()) {
+                return Err(Some(format!("Snippets can only be inserted into C++ sources, but {file} isn't one. ")));
+            }
+            let source = match fs::read_to_string(&file) {
+                Ok(source) => source,
+                Err(err) => return Err(Some(format!("Cannot read {file}: {err}"))),
+            };
+            let resolved = snippets::resolve(registry, names)?;
+            let (source, inserted) = snippets::insert(&source, &resolved);
+            if inserted.is_empty() {
+                println!("All the snippets are already in {}. ", file);
+                return Ok(());
+            }
+            if let Err(err) = fs::write(&file, source) {
+                return Err(Some(format!("Cannot write {file}: {err}")));
+            }
+            println!("{}", format!("Inserted {} into {}", inserted.join(", "), file).green());
+        }
+
+        SnippetSubcommand::List => {
+            let builtin = resource::SNIPPETS.iter().map(|(name, _)| *name).collect::<Vec<&str>>();
+            let list = registry.list(&builtin);
+            let width = list.iter().map(|(name, _, _)| name.len()).max().unwrap_or(0);
+            let mut last = None;
+            for (name, origin, _) in &list {
+                // Only the first snippet of the same name is used.
+                if last == Some(name) {
+                    continue;
+                }
+                last = Some(name);
+                let snippet = snippets::load(registry, name)?;
+                let requires = if snippet.requires.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (requires {})", snippet.requires.join(", "))
+                };
+                println!("{:width$} {} {}{}", name, format!("[{}]", origin).dark_grey(), snippet.description, requires.dark_grey());
+            }
+        }
+
+        SnippetSubcommand::Show { name } => {
+            let Snippet { origin, requires, includes, body, .. } = snippets::load(registry, name)?;
+            eprintln!("{}", format!("[{}] requires: {}", origin, requires.join(", ")).dark_grey());
+            for include in includes {
+                println!("{}", include);
+            }
+            println!("{}", body);
+        }
+    }
+    Ok(())
+}
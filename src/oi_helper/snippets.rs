@@ -0,0 +1,314 @@
+//! This file contains the snippets, which are pieces of code inserted into C++ sources.
+//!
+//! A snippet starts with comment lines declaring it, followed by the code:
+//! ```text
+//! // description: Segment tree with range additions.
+//! // requires: fast_io, modint
+//! #include <vector>
+//! ...
+//! ```
+//! The `#include` lines are moved to the includes of the source, and the required snippets are inserted before.
+//! Inserted snippets are wrapped between markers, so they are never inserted twice.
+
+use std::fs;
+
+use super::{
+    resource,
+    templates::{Origin, Registry},
+};
+
+/// The line after which the snippets are inserted. Without it, snippets are inserted before `main`.
+pub const MARKER: &str = "// @snippets";
+
+/// A parsed snippet.
+pub struct Snippet {
+    pub name: String,
+    pub description: String,
+    /// The names of the snippets required by this one.
+    pub requires: Vec<String>,
+    /// The `#include` lines.
+    pub includes: Vec<String>,
+    /// The code without the header and the includes.
+    pub body: String,
+    pub origin: Origin,
+}
+
+impl Snippet {
+    /// Parse the content of a snippet.
+    pub fn parse(name: &str, content: &str, origin: Origin) -> Self {
+        let mut description = String::new();
+        let mut requires = Vec::new();
+        let mut includes = Vec::new();
+        let mut body = String::new();
+        let mut in_header = true;
+        for line in content.trim().lines() {
+            if in_header {
+                if let Some(comment) = line.trim().strip_prefix("//") {
+                    let comment = comment.trim();
+                    if let Some(value) = comment.strip_prefix("description:") {
+                        description = value.trim().to_string();
+                        continue;
+                    }
+                    if let Some(value) = comment.strip_prefix("requires:") {
+                        requires.extend(value.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()).map(String::from));
+                        continue;
+                    }
+                }
+                in_header = false;
+            }
+            if include_target(line).is_some() {
+                includes.push(line.trim().to_string());
+            } else {
+                body.push_str(line);
+                body.push('\n');
+            }
+        }
+        Self {
+            name: name.to_string(),
+            description,
+            requires,
+            includes,
+            body: body.trim().to_string(),
+            origin,
+        }
+    }
+
+    /// The line starting the snippet in sources.
+    fn begin_marker(&self) -> String {
+        format!("// ---- snippet {} ----", self.name)
+    }
+
+    /// The line ending the snippet in sources.
+    fn end_marker(&self) -> String {
+        format!("// ---- end of snippet {} ----", self.name)
+    }
+}
+
+/// Get the header of an `#include` line, e.g. `<vector>` or `"lib.hpp"`.
+fn include_target(line: &str) -> Option<String> {
+    let target = line.trim().strip_prefix('#')?.trim_start().strip_prefix("include")?.trim();
+    if target.starts_with('<') || target.starts_with('"') {
+        Some(target.split_whitespace().collect::<Vec<&str>>().join(" "))
+    } else {
+        None
+    }
+}
+
+/// Load a snippet from the registry, or from the built-in snippets.
+pub fn load(registry: &Registry, name: &str) -> Result<Snippet, String> {
+    if let Some((origin, path)) = registry.find(name) {
+        return match fs::read_to_string(&path) {
+            Ok(content) => Ok(Snippet::parse(name, &content, origin)),
+            Err(err) => Err(format!("Cannot read {}: {}", path.display(), err)),
+        };
+    }
+    match resource::SNIPPETS.iter().find(|(n, _)| *n == name) {
+        Some((_, content)) => Ok(Snippet::parse(name, content, Origin::Builtin)),
+        None => Err(format!("Snippet `{name}` is not found. Use `oi_helper snippet list` to see the snippets. ")),
+    }
+}
+
+/// Load the snippets and the ones they require, ordered so every snippet comes after the ones it requires.
+pub fn resolve(registry: &Registry, names: &[String]) -> Result<Vec<Snippet>, String> {
+    fn visit(registry: &Registry, name: &str, path: &mut Vec<String>, result: &mut Vec<Snippet>) -> Result<(), String> {
+        if result.iter().any(|s| s.name == name) {
+            return Ok(());
+        }
+        if path.iter().any(|n| n == name) {
+            return Err(format!("Snippets require each other: {} -> {}", path.join(" -> "), name));
+        }
+        let snippet = load(registry, name)?;
+        path.push(name.to_string());
+        for required in &snippet.requires {
+            visit(registry, required, path, result)?;
+        }
+        path.pop();
+        result.push(snippet);
+        Ok(())
+    }
+
+    let mut result = Vec::new();
+    for name in names {
+        visit(registry, name, &mut Vec::new(), &mut result)?;
+    }
+    Ok(result)
+}
+
+/// Insert the snippets into the source. The snippets already in the source are skipped.
+/// Returns the new source and the names of the inserted snippets.
+pub fn insert(source: &str, snippets: &[Snippet]) -> (String, Vec<String>) {
+    let lines = source.split_inclusive('\n').collect::<Vec<&str>>();
+    let existing = |snippet: &Snippet| lines.iter().any(|line| line.trim() == snippet.begin_marker());
+    let new_snippets = snippets.iter().filter(|s| !existing(s)).collect::<Vec<&Snippet>>();
+    if new_snippets.is_empty() {
+        return (source.to_string(), vec![]);
+    }
+
+    // Only add the includes the source doesn't have. `bits/stdc++.h` has all the standard headers.
+    let mut included = lines.iter().filter_map(|line| include_target(line)).collect::<Vec<String>>();
+    let has_stdcpp = included.iter().any(|i| i == "<bits/stdc++.h>");
+    let mut includes = String::new();
+    for include in new_snippets.iter().flat_map(|s| s.includes.iter()) {
+        let target = include_target(include).unwrap_or_default();
+        if included.contains(&target) || (has_stdcpp && target.starts_with('<')) {
+            continue;
+        }
+        includes.push_str(include);
+        includes.push('\n');
+        included.push(target);
+    }
+
+    let mut code = String::new();
+    for snippet in &new_snippets {
+        code.push_str(&format!("{}\n{}\n{}\n\n", snippet.begin_marker(), snippet.body, snippet.end_marker()));
+    }
+
+    // The includes go after the last include.
+    // The code goes after the snippets inserted before, so the ones required come first,
+    // or after the marker, or before `main`.
+    let include_at = lines.iter().rposition(|line| include_target(line).is_some()).map(|i| i + 1).unwrap_or(0);
+    let last_snippet = lines.iter().rposition(|line| {
+        let line = line.trim();
+        line.starts_with("// ---- end of snippet ") && line.ends_with(" ----")
+    });
+    let code_at = match last_snippet.or_else(|| lines.iter().position(|line| line.trim() == MARKER)) {
+        Some(i) => i + 1,
+        None => lines
+            .iter()
+            .position(|line| line.trim_start().starts_with("int main") || line.trim_start().starts_with("signed main"))
+            .unwrap_or(lines.len()),
+    };
+
+    let mut result = String::new();
+    for i in 0..=lines.len() {
+        if i == include_at {
+            result.push_str(&includes);
+        }
+        if i == code_at {
+            if !result.is_empty() && !result.ends_with('\n') {
+                result.push('\n');
+            }
+            // Separate the code from the snippet before.
+            if last_snippet.is_some() {
+                result.push('\n');
+            }
+            result.push_str(code.trim_end());
+            result.push('\n');
+            if last_snippet.is_none() {
+                result.push('\n');
+            }
+        }
+        if let Some(line) = lines.get(i) {
+            result.push_str(line);
+        }
+    }
+    (result, new_snippets.iter().map(|s| s.name.clone()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, content: &str) -> Snippet {
+        Snippet::parse(name, content, Origin::Builtin)
+    }
+
+    fn names(snippets: &[Snippet]) -> Vec<&str> {
+        snippets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn builtin_registry() -> Registry {
+        Registry::new(&None, None, "snippets")
+    }
+
+    #[test]
+    fn parse_header() {
+        let s = snippet("a", "// description: A.\n// requires: b, c d\n#include <vector>\n// not the header\nint a;\n");
+        assert_eq!(s.description, "A.");
+        assert_eq!(s.requires, vec!["b", "c", "d"]);
+        assert_eq!(s.includes, vec!["#include <vector>"]);
+        assert_eq!(s.body, "// not the header\nint a;");
+    }
+
+    #[test]
+    fn resolve_dependencies_first() {
+        let resolved = resolve(&builtin_registry(), &[String::from("dijkstra"), String::from("lazy_segtree")]).unwrap();
+        assert_eq!(names(&resolved), vec!["graph", "dijkstra", "fast_io", "lazy_segtree"]);
+        let resolved = resolve(&builtin_registry(), &[String::from("graph"), String::from("dijkstra"), String::from("graph")]).unwrap();
+        assert_eq!(names(&resolved), vec!["graph", "dijkstra"]);
+        assert!(resolve(&builtin_registry(), &[String::from("no_such_snippet")]).is_err());
+    }
+
+    #[test]
+    fn resolve_cycles() {
+        let root = std::env::temp_dir().join(format!("oi_helper_snippets_test_{}", std::process::id()));
+        let dir = root.join(".oi_helper").join("snippets");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.cc"), "// requires: b\nint a;\n").unwrap();
+        fs::write(dir.join("b.cc"), "// requires: a\nint b;\n").unwrap();
+        let registry = Registry::new(&None, Some(&root), "snippets");
+        let err = resolve(&registry, &[String::from("a")]).err().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert!(err.contains("a -> b -> a"), "{err}");
+    }
+
+    #[test]
+    fn insert_before_main() {
+        let source = "#include <cstdio>\n\nint main() {\n}\n";
+        let (result, inserted) = insert(source, &[snippet("a", "#include <vector>\nint a;")]);
+        assert_eq!(inserted, vec!["a"]);
+        assert_eq!(
+            result,
+            "#include <cstdio>\n#include <vector>\n\n// ---- snippet a ----\nint a;\n// ---- end of snippet a ----\n\nint main() {\n}\n"
+        );
+    }
+
+    #[test]
+    fn insert_at_marker() {
+        let source = "#include <cstdio>\n// @snippets\nint n;\n\nint main() {\n}\n";
+        let (result, _) = insert(source, &[snippet("a", "int a;")]);
+        let marker = result.find(MARKER).unwrap();
+        let code = result.find("// ---- snippet a ----").unwrap();
+        assert!(marker < code && code < result.find("int n;").unwrap());
+    }
+
+    #[test]
+    fn no_double_insertion() {
+        let source = "int main() {\n}\n";
+        let (once, _) = insert(source, &[snippet("a", "int a;")]);
+        let (twice, inserted) = insert(&once, &[snippet("a", "int a;")]);
+        assert_eq!(twice, once);
+        assert!(inserted.is_empty());
+
+        // Only the missing ones are inserted, after the ones inserted before.
+        let (result, inserted) = insert(&once, &[snippet("a", "int a;"), snippet("b", "int b;")]);
+        assert_eq!(inserted, vec!["b"]);
+        assert_eq!(result.matches("// ---- snippet a ----").count(), 1);
+        assert!(result.find("end of snippet a").unwrap() < result.find("snippet b ----").unwrap());
+    }
+
+    #[test]
+    fn dependencies_inserted_first() {
+        let resolved = resolve(&builtin_registry(), &[String::from("dijkstra")]).unwrap();
+        let (result, inserted) = insert("int main() {\n}\n", &resolved);
+        assert_eq!(inserted, vec!["graph", "dijkstra"]);
+        assert!(result.find("// ---- snippet graph ----").unwrap() < result.find("// ---- snippet dijkstra ----").unwrap());
+    }
+
+    #[test]
+    fn no_duplicate_includes() {
+        let source = "#include <vector>\n\nint main() {\n}\n";
+        let snippets = [snippet("a", "#include <vector>\n#include <queue>\nint a;"), snippet("b", "#include  <queue>\nint b;")];
+        let (result, _) = insert(source, &snippets);
+        assert_eq!(result.matches("<vector>").count(), 1);
+        assert_eq!(result.matches("<queue>").count(), 1);
+    }
+
+    #[test]
+    fn no_standard_includes_with_bits() {
+        let source = "#include <bits/stdc++.h>\n\nint main() {\n}\n";
+        let (result, _) = insert(source, &[snippet("a", "#include <vector>\n#include \"lib.hpp\"\nint a;")]);
+        assert!(!result.contains("<vector>"));
+        assert!(result.contains("#include <bits/stdc++.h>\n#include \"lib.hpp\"\n"));
+    }
+}
@@ -0,0 +1,252 @@
+//! This file contains functions for saving problem statements as local Markdown files.
+
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use crossterm::style::Stylize;
+
+use super::utils::web::{self, FetchedProblem};
+
+/// Guess the extension of an image from its URL.
+fn image_extension(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    match path.rsplit_once('.') {
+        Some((_, ext)) if matches!(ext.to_lowercase().as_str(), "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "bmp") => ext,
+        _ => "png",
+    }
+}
+
+/// Get the length of the run of `c` at the start of `bytes`.
+fn run_length(bytes: &[u8], c: u8) -> usize {
+    bytes.iter().take_while(|b| **b == c).count()
+}
+
+/// Find the end of the link of an image, i.e. the `)` matching the `(` before `start`.
+/// Parentheses in the link must be balanced, and the link cannot span lines.
+fn link_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 1;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            b'\n' => return None,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Find the links of the images `![alt](link)` in the Markdown, as the byte ranges of the links.
+/// The images in fenced code blocks and inline code are skipped.
+fn image_links(markdown: &str) -> Vec<(usize, usize)> {
+    let bytes = markdown.as_bytes();
+    let mut result = Vec::new();
+    // The character and the length of the fence of the current code block.
+    let mut fence: Option<(u8, usize)> = None;
+    let mut i = 0;
+    let mut line_start = true;
+
+    while i < bytes.len() {
+        if line_start {
+            line_start = false;
+            let line_end = markdown[i..].find('\n').map(|pos| i + pos + 1).unwrap_or(bytes.len());
+            let indent = run_length(&bytes[i..], b' ');
+            let marker = bytes.get(i + indent).copied().filter(|c| *c == b'`' || *c == b'~');
+            let fence_length = marker.map(|c| run_length(&bytes[i + indent..], c)).unwrap_or(0);
+            if indent < 4 && fence_length >= 3 {
+                let c = marker.unwrap_or_default();
+                fence = match fence {
+                    None => Some((c, fence_length)),
+                    Some((open, length)) if open == c && fence_length >= length => None,
+                    other => other,
+                };
+                i = line_end;
+                line_start = true;
+                continue;
+            }
+            if fence.is_some() {
+                i = line_end;
+                line_start = true;
+                continue;
+            }
+        }
+        match bytes[i] {
+            b'\n' => {
+                line_start = true;
+                i += 1;
+            }
+            b'`' => {
+                // Inline code ends with a run of backticks of the same length, in the same paragraph.
+                let length = run_length(&bytes[i..], b'`');
+                let paragraph_end = markdown[i..].find("\n\n").map(|pos| i + pos).unwrap_or(bytes.len());
+                let mut j = i + length;
+                i += length;
+                while j < paragraph_end {
+                    let run = run_length(&bytes[j..paragraph_end], b'`');
+                    if run == length {
+                        i = j + run;
+                        break;
+                    }
+                    j += run.max(1);
+                }
+            }
+            b'!' if bytes.get(i + 1) == Some(&b'[') => {
+                let link_start = markdown[i..].find("](").map(|pos| i + pos + 2);
+                match link_start.and_then(|start| Some((start, link_end(bytes, start)?))) {
+                    Some((start, end)) => {
+                        result.push((start, end));
+                        i = end + 1;
+                    }
+                    None => i += 2,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    result
+}
+
+/// Download the images in the Markdown into `assets_dir` and make the links point to the local files.
+fn localize_images(markdown: &str, base_url: &str, assets_dir: &Path) -> String {
+    let assets_dir_name = assets_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let mut result = String::new();
+    let mut last = 0;
+    let mut counter = 0;
+
+    for (link_start, link_end) in image_links(markdown) {
+        // The link is `url "title"`, and the URL ends at the first whitespace.
+        let link = markdown[link_start..link_end].trim();
+        let url = link.split_whitespace().next().unwrap_or("");
+        let title = link[url.len()..].trim();
+
+        result.push_str(&markdown[last..link_start]);
+        let remote = web::resolve_url(base_url, url);
+        let file_name = format!("{}.{}", counter, image_extension(&remote));
+        let downloaded = web::get_remotely_bytes(&remote)
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                fs::create_dir_all(assets_dir).map_err(|err| err.to_string())?;
+                fs::write(assets_dir.join(&file_name), bytes).map_err(|err| err.to_string())
+            });
+        match downloaded {
+            Ok(_) => {
+                eprintln!("Downloaded image {}. ", remote);
+                result.push_str(&format!("{}/{}", assets_dir_name, file_name));
+                counter += 1;
+            }
+            Err(err) => {
+                eprintln!("{} Cannot download image {}: {}", "[WARNING]".bold().yellow(), remote, err);
+                result.push_str(&remote);
+            }
+        }
+        if !title.is_empty() {
+            result.push(' ');
+            result.push_str(title);
+        }
+        last = link_end;
+    }
+    result.push_str(&markdown[last..]);
+    result
+}
+
+/// Render the whole statement as Markdown.
+fn render(problem: &FetchedProblem) -> String {
+    let mut result = format!("# {}\n\n", problem.title);
+    result.push_str(&format!("- Source: <{}>\n", problem.url));
+    result.push_str(&format!("- Time limit: {}ms\n", problem.time_limit));
+    result.push_str(&format!("- Memory limit: {}MB\n", problem.memory_limit));
+    if let Some(difficulty) = &problem.difficulty {
+        result.push_str(&format!("- Difficulty: {}\n", difficulty));
+    }
+    if !problem.tags.is_empty() {
+        result.push_str(&format!("- Tags: {}\n", problem.tags.join(", ")));
+    }
+    for (heading, content) in &problem.statement {
+        result.push('\n');
+        if !heading.is_empty() {
+            result.push_str(&format!("## {}\n\n", heading));
+        }
+        result.push_str(content);
+        result.push('\n');
+    }
+    result
+}
+
+/// Download the statement of the problem `spec` (e.g. `luogu:P1001`) and save it as `name.md`.
+/// The images are saved into `name.assets`.
+pub fn save_statement(spec: &str, name: Option<&str>) -> Result<(), Option<String>> {
+    eprintln!("Fetching the statement of {}... ", spec);
+    let problem = match web::fetch_problem(spec) {
+        Ok(problem) => problem,
+        Err(err) => {
+            return Err(Some(format!("Error occured while fetching the statement: {}", err)));
+        }
+    };
+    let name = name.unwrap_or(&problem.problem_id);
+
+    let assets_dir = format!("./{}.assets", name);
+    let markdown = localize_images(&render(&problem), &problem.url, Path::new(&assets_dir));
+
+    let path_str = format!("./{}.md", name);
+    let mut file = match File::create(Path::new(&path_str)) {
+        Ok(file) => file,
+        Err(err) => {
+            return Err(Some(format!("Cannot create {}: {}", path_str, err)));
+        }
+    };
+    if let Err(err) = file.write_all(markdown.as_bytes()) {
+        return Err(Some(format!("Cannot write to {}: {}", path_str, err)));
+    }
+
+    eprintln!("{}", format!("Saved the statement to {}. ", path_str).green());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Get the links of the images in the Markdown.
+    fn links(markdown: &str) -> Vec<&str> {
+        image_links(markdown).into_iter().map(|(start, end)| &markdown[start..end]).collect()
+    }
+
+    #[test]
+    fn images() {
+        assert_eq!(links("![a](x.png) and ![b](y.png \"title\")"), vec!["x.png", "y.png \"title\""]);
+        assert_eq!(links("[link](x.png) ![](z.gif)"), vec!["z.gif"]);
+        assert_eq!(links("如图：![图 1](图1.png)。"), vec!["图1.png"]);
+    }
+
+    #[test]
+    fn parentheses_in_links() {
+        assert_eq!(links("![a](https://a.com/f_(1).png) (note)"), vec!["https://a.com/f_(1).png"]);
+        assert_eq!(links("![a](x\\).png)"), vec!["x\\).png"]);
+        assert_eq!(links("![a](x.png\n)"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn images_in_code() {
+        let markdown = "```cpp\n![a](in_fence.png)\n```\n![b](b.png)\n~~~~\n```\n![c](c.png)\n~~~~\n";
+        assert_eq!(links(markdown), vec!["b.png"]);
+        assert_eq!(links("`![a](a.png)` ![b](b.png) ``x ` ![c](c.png)`` ![d](d.png)"), vec!["b.png", "d.png"]);
+        assert_eq!(links("` ![a](a.png)\n\n![b](b.png)"), vec!["a.png", "b.png"]);
+    }
+
+    #[test]
+    fn unchanged_without_images() {
+        let markdown = "# Title\n\n```\n![a](a.png)\n```\n";
+        assert_eq!(localize_images(markdown, "https://a.com/", Path::new("p.assets")), markdown);
+    }
+}
@@ -0,0 +1,290 @@
+//! This file contains the statistics of the problems in a workspace, from their metadata and test history.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crossterm::style::Stylize;
+use json::{object, JsonValue};
+
+use super::{
+    history::{Entry, History},
+    problem::{ProblemInfo, Status},
+    utils::{datetime, table::Table},
+};
+
+/// How many weeks the timeline shows.
+const TIMELINE_WEEKS: u64 = 8;
+/// The width of the bars in the timeline.
+const BAR_WIDTH: usize = 20;
+const WEEK: u64 = 7 * 86400;
+
+/// A problem of the workspace, named after its sample group relative to the workspace, e.g. `luogu/P1001`.
+struct Problem {
+    name: String,
+    info: ProblemInfo,
+    history: Vec<Entry>,
+    /// When the sample group was last modified, used for the problems that have never been tested.
+    modified: Option<u64>,
+}
+
+impl Problem {
+    fn attempted(&self) -> bool {
+        self.info.attempts() > 0 || self.info.status() != Status::Todo
+    }
+
+    fn solved(&self) -> bool {
+        self.info.status() == Status::Accepted
+    }
+
+    /// Get when the problem was last tested, or when it was fetched if it has never been tested.
+    fn last_active(&self) -> Option<u64> {
+        self.info.timestamp("last_attempt").or(self.modified)
+    }
+}
+
+/// Get the modification time of a file or directory, in seconds since the Unix epoch.
+fn modified_time(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+    modified.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+/// How many problems there are, have been attempted, and have been solved.
+#[derive(Default)]
+struct Count {
+    total: usize,
+    attempted: usize,
+    solved: usize,
+}
+
+impl Count {
+    fn add(&mut self, problem: &Problem) {
+        self.total += 1;
+        self.attempted += problem.attempted() as usize;
+        self.solved += problem.solved() as usize;
+    }
+
+    fn to_json(&self) -> JsonValue {
+        object! {
+            "total": self.total,
+            "attempted": self.attempted,
+            "solved": self.solved,
+        }
+    }
+}
+
+/// Format a ratio as a percentage, or `-` if there is nothing to divide.
+fn percent(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        String::from("-")
+    } else {
+        format!("{:.0}%", part as f64 * 100.0 / whole as f64)
+    }
+}
+
+/// Find the sample groups in the directory, recursively.
+/// The build directory, hidden directories and statement assets are skipped.
+fn find_groups(dir: &Path) -> Vec<PathBuf> {
+    let mut result = Vec::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return result,
+    };
+    let mut paths = entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).filter(|path| path.is_dir()).collect::<Vec<PathBuf>>();
+    paths.sort();
+    for path in paths {
+        let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        if name.ends_with(".smpd") {
+            result.push(path);
+        } else if name != "target" && !name.starts_with('.') && !name.ends_with(".assets") {
+            result.extend(find_groups(&path));
+        }
+    }
+    result
+}
+
+/// Load the metadata of every problem in the workspace.
+fn load_problems(root: &Path) -> Result<Vec<Problem>, Option<String>> {
+    let mut problems = Vec::new();
+    for group in find_groups(root) {
+        let name = group.strip_prefix(root).unwrap_or(&group).with_extension("");
+        problems.push(Problem {
+            name: name.to_string_lossy().replace('\\', "/"),
+            info: ProblemInfo::open(&group)?,
+            history: History::open(&group).load(),
+            modified: modified_time(&group),
+        });
+    }
+    Ok(problems)
+}
+
+/// Count the problems grouped by `keys`, which gives the groups of a problem, like its tags.
+/// The groups with the most problems come first.
+fn count_by(problems: &[Problem], keys: impl Fn(&Problem) -> Vec<String>) -> Vec<(String, Count)> {
+    let mut counts = BTreeMap::<String, Count>::new();
+    for problem in problems {
+        for key in keys(problem) {
+            counts.entry(key).or_default().add(problem);
+        }
+    }
+    let mut counts = counts.into_iter().collect::<Vec<(String, Count)>>();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(count.total));
+    counts
+}
+
+/// Count the tests in each week, and how many of them passed, from the oldest week.
+/// The problems without history count their last test from the metadata.
+fn timeline(problems: &[Problem], now: u64) -> Vec<(u64, usize, usize)> {
+    let start = now - (now % 86400) + 86400 - TIMELINE_WEEKS * WEEK;
+    let mut weeks = (0..TIMELINE_WEEKS).map(|i| (start + i * WEEK, 0, 0)).collect::<Vec<(u64, usize, usize)>>();
+    for problem in problems {
+        let tests = if problem.history.is_empty() {
+            problem.info.timestamp("last_attempt").map(|time| (time, problem.solved())).into_iter().collect()
+        } else {
+            problem.history.iter().map(|entry| (entry.time, entry.passed())).collect::<Vec<(u64, bool)>>()
+        };
+        for (time, passed) in tests.into_iter().filter(|(time, _)| *time >= start) {
+            let week = &mut weeks[(((time - start) / WEEK) as usize).min(TIMELINE_WEEKS as usize - 1)];
+            week.1 += 1;
+            week.2 += passed as usize;
+        }
+    }
+    weeks
+}
+
+/// Print a table of counts.
+fn print_counts(title: &str, column: &str, counts: &[(String, Count)]) {
+    if counts.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", title.bold());
+    let mut table = Table::new(&[column, "Problems", "Attempted", "Solved", "Solved %"]);
+    for (name, count) in counts {
+        table.push(vec![
+            name.clone(),
+            count.total.to_string(),
+            count.attempted.to_string(),
+            count.solved.to_string(),
+            percent(count.solved, count.total),
+        ]);
+    }
+    table.print();
+}
+
+/// Print the statistics of the problems in the workspace at `root`, and export them into `json` as JSON if it's given.
+/// The unsolved problems not tested in `days` days are listed.
+pub fn stats(root: &Path, days: u64, json: Option<&Path>) -> Result<(), Option<String>> {
+    let problems = load_problems(root)?;
+    if problems.is_empty() {
+        println!("No problems found. Problems are found by their sample groups (`NAME.smpd`). ");
+        return Ok(());
+    }
+    let now = datetime::unix_now();
+
+    let mut total = Count::default();
+    for problem in &problems {
+        total.add(problem);
+    }
+    let by_oj = count_by(&problems, |problem| {
+        let oj = problem.info.get_str("oj");
+        vec![if oj.is_empty() { String::from("(unknown)") } else { oj }]
+    });
+    let by_difficulty = count_by(&problems, |problem| {
+        let difficulty = problem.info.get_str("difficulty");
+        vec![if difficulty.is_empty() { String::from("(unknown)") } else { difficulty }]
+    });
+    let by_tag = count_by(&problems, |problem| problem.info.tags());
+    let timeline = timeline(&problems, now);
+    let mut stale = problems
+        .iter()
+        .filter(|problem| !problem.solved())
+        .filter_map(|problem| Some((problem, problem.last_active()?)))
+        .filter(|(_, time)| now.saturating_sub(*time) >= days.saturating_mul(86400))
+        .collect::<Vec<(&Problem, u64)>>();
+    stale.sort_by_key(|(_, time)| *time);
+
+    println!(
+        "{} problems, {} attempted, {} solved ({} of the attempted). ",
+        total.total.to_string().bold(),
+        total.attempted.to_string().bold(),
+        total.solved.to_string().bold().green(),
+        percent(total.solved, total.attempted)
+    );
+    print_counts("By OJ", "OJ", &by_oj);
+    print_counts("By difficulty", "Difficulty", &by_difficulty);
+    print_counts("By tag", "Tag", &by_tag);
+
+    println!();
+    println!("{}", "Tests by week".bold());
+    let mut table = Table::new(&["Week of", "Tested", "AC", "AC rate", ""]);
+    for (start, tested, accepted) in &timeline {
+        let bar = if *tested == 0 { 0 } else { accepted * BAR_WIDTH / tested };
+        table.push(vec![
+            datetime::format_date(*start),
+            tested.to_string(),
+            accepted.to_string(),
+            percent(*accepted, *tested),
+            format!("{}{}", "#".repeat(bar), ".".repeat(if *tested == 0 { 0 } else { BAR_WIDTH - bar })),
+        ]);
+    }
+    table.print();
+
+    println!();
+    println!("{}", format!("Unsolved, not tested in {} day(s)", days).bold());
+    if stale.is_empty() {
+        println!("None. ");
+    } else {
+        let mut table = Table::new(&["Problem", "Status", "Attempts", "Last tested"]);
+        for (problem, _) in &stale {
+            table.push(vec![
+                problem.name.clone(),
+                problem.info.status().to_string(),
+                problem.info.attempts().to_string(),
+                problem.info.date("last_attempt").unwrap_or_else(|| String::from("never")),
+            ]);
+        }
+        table.print();
+    }
+
+    if let Some(path) = json {
+        let counts_json = |counts: &[(String, Count)]| {
+            counts
+                .iter()
+                .map(|(name, count)| {
+                    let mut entry = count.to_json();
+                    entry["name"] = name.as_str().into();
+                    entry
+                })
+                .collect::<Vec<JsonValue>>()
+        };
+        let export = object! {
+            "generated_at": now,
+            "days": days,
+            "problems": total.to_json(),
+            "by_oj": counts_json(&by_oj),
+            "by_difficulty": counts_json(&by_difficulty),
+            "by_tag": counts_json(&by_tag),
+            "timeline": timeline.iter().map(|(start, tested, accepted)| object! {
+                "week_of": datetime::format_date(*start),
+                "tested": *tested,
+                "accepted": *accepted,
+            }).collect::<Vec<JsonValue>>(),
+            "stale": stale.iter().map(|(problem, time)| object! {
+                "name": problem.name.clone(),
+                "status": problem.info.status().to_string(),
+                "attempts": problem.info.attempts(),
+                "last_attempt": problem.info.timestamp("last_attempt"),
+                "last_active": *time,
+            }).collect::<Vec<JsonValue>>(),
+        };
+        if let Err(err) = fs::write(path, export.pretty(4)) {
+            return Err(Some(format!("Cannot write {}: {}", path.display(), err)));
+        }
+        println!();
+        println!("{}", format!("Wrote {}", path.display()).green());
+    }
+    Ok(())
+}
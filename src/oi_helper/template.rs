@@ -0,0 +1,312 @@
+//! This file contains the template engine used when creating source files.
+//!
+//! The syntax:
+//! - `{##}` is the name of the source file;
+//! - `{#var#}` is the value of a variable. Unknown variables are kept as they are, so old templates render unchanged;
+//! - `{#if var#}...{#else#}...{#end#}` keeps one of the branches, depending on whether the variable is set,
+//!   non-empty and not `false` or `0`. `{#if !var#}` negates the condition;
+//! - `{#include path#}` inserts another template, relative to the directory of the including template.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The variables and the directory used for resolving includes.
+pub struct Context {
+    vars: BTreeMap<String, String>,
+    base_dir: PathBuf,
+}
+
+/// A node of a parsed template.
+enum Node {
+    Text(String),
+    /// A variable, and the original text which is kept if the variable is unknown.
+    Var(String, String),
+    If {
+        var: String,
+        negate: bool,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    Include(String),
+}
+
+/// How deep the includes can be nested, which stops templates including each other forever.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+impl Context {
+    pub fn new(base_dir: &Path) -> Self {
+        Self {
+            vars: BTreeMap::new(),
+            base_dir: base_dir.to_path_buf(),
+        }
+    }
+
+    /// Set a variable. `{##}` is the variable with an empty name.
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.vars.insert(name.to_string(), value.to_string());
+    }
+
+    fn truthy(&self, name: &str) -> bool {
+        match self.vars.get(name) {
+            Some(value) => !matches!(value.trim(), "" | "false" | "0"),
+            None => false,
+        }
+    }
+
+    /// Render a template.
+    pub fn render(&self, template: &str) -> Result<String, String> {
+        self.render_in(template, &self.base_dir, 0)
+    }
+
+    fn render_in(&self, template: &str, dir: &Path, depth: usize) -> Result<String, String> {
+        let tokens = tokenize(template);
+        let mut pos = 0;
+        let nodes = parse(&tokens, &mut pos, false)?;
+        let mut result = String::new();
+        self.render_nodes(&nodes, dir, depth, &mut result)?;
+        Ok(result)
+    }
+
+    fn render_nodes(&self, nodes: &[Node], dir: &Path, depth: usize, result: &mut String) -> Result<(), String> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => result.push_str(text),
+                Node::Var(name, raw) => match self.vars.get(name) {
+                    Some(value) => result.push_str(value),
+                    None => result.push_str(raw),
+                },
+                Node::If { var, negate, then, otherwise } => {
+                    let branch = if self.truthy(var) != *negate { then } else { otherwise };
+                    self.render_nodes(branch, dir, depth, result)?;
+                }
+                Node::Include(path) => {
+                    if depth >= MAX_INCLUDE_DEPTH {
+                        return Err(format!("too many nested includes when including `{path}`"));
+                    }
+                    let path = dir.join(path);
+                    let content = fs::read_to_string(&path).map_err(|err| format!("cannot include {}: {}", path.display(), err))?;
+                    let included_dir = path.parent().unwrap_or(dir).to_path_buf();
+                    result.push_str(&self.render_in(&content, &included_dir, depth + 1)?);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A token of a template: a text, or a tag with its inner text and its original text.
+enum Token {
+    Text(String),
+    Tag(String, String),
+}
+
+/// Check if a tag is `{#if ...#}`, `{#else#}` or `{#end#}`.
+fn is_block_tag(tag: &str) -> bool {
+    tag == "else" || tag == "end" || tag.starts_with("if ")
+}
+
+fn tokenize(template: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+    while let Some(found) = template[pos..].find("{#") {
+        let start = pos + found;
+        let end = match template[start + 2..].find("#}") {
+            Some(end) => start + 2 + end,
+            None => break,
+        };
+        let tag = template[start + 2..end].trim().to_string();
+        let raw = template[start..end + 2].to_string();
+        let mut text_end = start;
+        pos = end + 2;
+
+        // A block tag on its own line takes the whole line, so blocks don't leave empty lines.
+        if is_block_tag(&tag) {
+            let line_start = template[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            let rest = &template[pos..];
+            let line_break = if rest.starts_with('\n') {
+                Some(1)
+            } else if rest.starts_with("\r\n") {
+                Some(2)
+            } else if rest.is_empty() {
+                Some(0)
+            } else {
+                None
+            };
+            if let Some(line_break) = line_break {
+                if line_start >= text_start && template[line_start..start].trim().is_empty() {
+                    text_end = line_start;
+                    pos += line_break;
+                }
+            }
+        }
+
+        if text_end > text_start {
+            tokens.push(Token::Text(template[text_start..text_end].to_string()));
+        }
+        tokens.push(Token::Tag(tag, raw));
+        text_start = pos;
+    }
+    if text_start < template.len() {
+        tokens.push(Token::Text(template[text_start..].to_string()));
+    }
+    tokens
+}
+
+/// Parse the tokens until `{#else#}`, `{#end#}` or the end. `in_block` tells if an `{#end#}` is expected.
+fn parse(tokens: &[Token], pos: &mut usize, in_block: bool) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new();
+    while *pos < tokens.len() {
+        match &tokens[*pos] {
+            Token::Text(text) => {
+                nodes.push(Node::Text(text.clone()));
+                *pos += 1;
+            }
+            Token::Tag(tag, raw) => {
+                let (keyword, argument) = tag.split_once(char::is_whitespace).map(|(k, a)| (k, a.trim())).unwrap_or((tag, ""));
+                match keyword {
+                    "else" | "end" if in_block => return Ok(nodes),
+                    "else" | "end" => return Err(format!("`{raw}` without a matching `{{#if ...#}}`")),
+                    "if" if !argument.is_empty() => {
+                        let (var, negate) = match argument.strip_prefix('!') {
+                            Some(var) => (var.trim(), true),
+                            None => (argument, false),
+                        };
+                        *pos += 1;
+                        let then = parse(tokens, pos, true)?;
+                        let mut otherwise = vec![];
+                        if matches!(&tokens[*pos], Token::Tag(tag, _) if tag == "else") {
+                            *pos += 1;
+                            otherwise = parse(tokens, pos, true)?;
+                        }
+                        if !matches!(tokens.get(*pos), Some(Token::Tag(tag, _)) if tag == "end") {
+                            return Err(format!("`{raw}` is not closed with `{{#end#}}`"));
+                        }
+                        nodes.push(Node::If { var: var.to_string(), negate, then, otherwise });
+                        *pos += 1;
+                    }
+                    "include" if !argument.is_empty() => {
+                        nodes.push(Node::Include(argument.to_string()));
+                        *pos += 1;
+                    }
+                    _ => {
+                        nodes.push(Node::Var(tag.clone(), raw.clone()));
+                        *pos += 1;
+                    }
+                }
+            }
+        }
+    }
+    if in_block {
+        return Err(String::from("`{#if ...#}` is not closed with `{#end#}`"));
+    }
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oi_helper::resource;
+
+    fn render(template: &str, vars: &[(&str, &str)]) -> Result<String, String> {
+        let mut context = Context::new(Path::new("."));
+        for (name, value) in vars {
+            context.set(name, value);
+        }
+        context.render(template)
+    }
+
+    /// Remove the lines from `{#if file_io#}` to `{#end#}`, which is what the builtin templates look like without file IO.
+    fn without_file_io(template: &str) -> String {
+        let mut result = String::new();
+        let mut skipping = false;
+        for line in template.split_inclusive('\n') {
+            match line.trim() {
+                "{#if file_io#}" => skipping = true,
+                "{#end#}" if skipping => skipping = false,
+                _ if !skipping => result.push_str(line),
+                _ => {}
+            }
+        }
+        result
+    }
+
+    static BUILTINS: [&str; 6] = [
+        resource::CPP_TEMPLATE_0,
+        resource::CPP_TEMPLATE_1,
+        resource::CPP_DP_TEMPLATE_0,
+        resource::CPP_DP_TEMPLATE_1,
+        resource::CPP_DP_2D_TEMPLATE_0,
+        resource::CPP_DP_2D_TEMPLATE_1,
+    ];
+
+    #[test]
+    fn builtins_without_file_io() {
+        for template in BUILTINS {
+            let rendered = render(template, &[]).unwrap();
+            assert_eq!(rendered, without_file_io(template));
+            assert!(!rendered.contains("freopen") && !rendered.contains("{#if"));
+        }
+    }
+
+    #[test]
+    fn builtins_with_file_io() {
+        for template in BUILTINS {
+            let rendered = render(template, &[("", "game.cc"), ("debug_kit", ""), ("maxn_value", "100"), ("maxl_value", "10"), ("file_io", "game")]).unwrap();
+            assert!(rendered.contains("#include <cstdio>\n"));
+            assert!(rendered.contains("    freopen(\"game.in\", \"r\", stdin);\n    freopen(\"game.out\", \"w\", stdout);\n"));
+            assert!(!rendered.contains("{#"));
+        }
+    }
+
+    #[test]
+    fn variables() {
+        assert_eq!(render("// {##}\nN = {#maxn_value#};", &[("", "a.cc"), ("maxn_value", "100")]).unwrap(), "// a.cc\nN = 100;");
+        assert_eq!(render("{#x#} {# y #} {#", &[]).unwrap(), "{#x#} {# y #} {#");
+    }
+
+    #[test]
+    fn conditions() {
+        let template = "{#if a#}A{#else#}-{#end#}{#if !a#}!{#end#}";
+        assert_eq!(render(template, &[("a", "1")]).unwrap(), "A");
+        for value in ["", "0", "false"] {
+            assert_eq!(render(template, &[("a", value)]).unwrap(), "-!");
+        }
+        assert_eq!(render(template, &[]).unwrap(), "-!");
+    }
+
+    #[test]
+    fn nested_conditions() {
+        let template = "a\n{#if x#}\nx\n  {#if y#}\nxy\n  {#else#}\nx!y\n  {#end#}\n{#end#}\nb\n";
+        assert_eq!(render(template, &[("x", "1"), ("y", "1")]).unwrap(), "a\nx\nxy\nb\n");
+        assert_eq!(render(template, &[("x", "1")]).unwrap(), "a\nx\nx!y\nb\n");
+        assert_eq!(render(template, &[("y", "1")]).unwrap(), "a\nb\n");
+    }
+
+    #[test]
+    fn unbalanced_blocks() {
+        assert!(render("{#if x#}never closed", &[]).is_err());
+        assert!(render("{#if x#}{#if y#}{#end#}", &[]).is_err());
+        assert!(render("stray {#end#}", &[]).is_err());
+        assert!(render("stray {#else#}", &[]).is_err());
+    }
+
+    #[test]
+    fn includes() {
+        let dir = std::env::temp_dir().join(format!("oi_helper_template_test_{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/part.cc"), "part {#name#}\n{#include more.cc#}").unwrap();
+        fs::write(dir.join("sub/more.cc"), "more").unwrap();
+        fs::write(dir.join("loop.cc"), "{#include loop.cc#}").unwrap();
+
+        let mut context = Context::new(&dir);
+        context.set("name", "x");
+        assert_eq!(context.render("{#include sub/part.cc#}!").unwrap(), "part x\nmore!");
+        assert!(context.render("{#include loop.cc#}").unwrap_err().contains("too many nested includes"));
+        assert!(context.render("{#include missing.cc#}").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
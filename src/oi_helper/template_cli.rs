@@ -0,0 +1,105 @@
+//! This file contains the `template` subcommand.
+
+use std::{fs, path::PathBuf};
+
+use crossterm::style::Stylize;
+
+use crate::TemplateSubcommand;
+
+use super::templates::{self, Origin, Registry};
+
+/// Get the directory to change, creating it if it doesn't exist.
+fn target_dir(registry: &Registry, local: bool) -> Result<PathBuf, Option<String>> {
+    let dir = match (local, registry.dir(if local { Origin::Workspace } else { Origin::Global })) {
+        (_, Some(dir)) => dir.to_path_buf(),
+        (true, None) => return Err(Some("Cannot find a workspace here. ".to_string())),
+        (false, None) => return Err(Some("Cannot find the global configuration directory. ".to_string())),
+    };
+    if let Err(err) = fs::create_dir_all(&dir) {
+        return Err(Some(format!("Cannot create {}: {}", dir.display(), err)));
+    }
+    Ok(dir)
+}
+
+/// Manage the templates. `scheme` is the `cc_template` used by the built-in templates.
+pub fn template(registry: &Registry, scheme: &str, subcommand: &TemplateSubcommand) -> Result<(), Option<String>> {
+    match subcommand {
+        TemplateSubcommand::List => {
+            let list = registry.list(templates::BUILTIN);
+            let width = list.iter().map(|(name, _, _)| name.len()).max().unwrap_or(0);
+            let mut last = None;
+            for (name, origin, path) in &list {
+                let location = match path {
+                    Some(path) => format!("[{}] {}", origin, path.display()),
+                    None => format!("[{}]", origin),
+                };
+                // Only the first template of the same name is used.
+                if last == Some(name) {
+                    println!("{:width$} {} {}", name, location.dark_grey(), "(overridden)".dark_grey());
+                } else {
+                    println!("{:width$} {}", name, location.dark_grey());
+                }
+                last = Some(name);
+            }
+        }
+
+        TemplateSubcommand::Show { name } => {
+            if let Some((origin, path)) = registry.find(name) {
+                match fs::read_to_string(&path) {
+                    Ok(content) => {
+                        eprintln!("{}", format!("[{}] {}", origin, path.display()).dark_grey());
+                        print!("{}", content);
+                    }
+                    Err(err) => return Err(Some(format!("Cannot read {}: {}", path.display(), err))),
+                }
+            } else if let Some(content) = templates::builtin(name, scheme) {
+                eprintln!("{}", format!("[{}] {} ({})", Origin::Builtin, name, scheme).dark_grey());
+                println!("{}", content);
+            } else {
+                return Err(Some(format!("Template `{name}` is not found. Use `oi_helper template list` to see the templates. ")));
+            }
+        }
+
+        TemplateSubcommand::Add { name, file, local, force } => {
+            templates::check_name(name)?;
+            let origin = if *local { Origin::Workspace } else { Origin::Global };
+            let dir = target_dir(registry, *local)?;
+            if let Some(existing) = registry.find_from(origin, name) {
+                if !force {
+                    return Err(Some(format!(
+                        "Template `{}` already exists in {}. Use `--force` to replace it. ",
+                        name,
+                        existing.display()
+                    )));
+                }
+                if let Err(err) = fs::remove_file(&existing) {
+                    return Err(Some(format!("Cannot remove {}: {}", existing.display(), err)));
+                }
+            }
+            // Keep the extension, so editors can highlight the template.
+            let path = match file.extension() {
+                Some(extension) => dir.join(format!("{}.{}", name, extension.to_string_lossy())),
+                None => dir.join(name),
+            };
+            if let Err(err) = fs::copy(file, &path) {
+                return Err(Some(format!("Cannot copy {} to {}: {}", file.display(), path.display(), err)));
+            }
+            if templates::BUILTIN.contains(&name.as_str()) {
+                eprintln!("{} The built-in template `{}` is overridden. ", "[WARNING]".bold().yellow(), name);
+            }
+            println!("{}", format!("Added template `{}` to {}", name, path.display()).green());
+        }
+
+        TemplateSubcommand::Rm { name, local } => {
+            let origin = if *local { Origin::Workspace } else { Origin::Global };
+            match registry.find_from(origin, name) {
+                Some(path) => match fs::remove_file(&path) {
+                    Ok(_) => println!("{}", format!("Removed {}", path.display()).green()),
+                    Err(err) => return Err(Some(format!("Cannot remove {}: {}", path.display(), err))),
+                },
+                None => return Err(Some(format!("Template `{name}` is not found in the {origin} templates. "))),
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,139 @@
+//! This file contains the registry of named templates, which is also used for snippets.
+//!
+//! Templates are looked up by name in this order:
+//! - the workspace's templates in `<workspace>/.oi_helper/templates`;
+//! - the user's templates in `~/.oi_helper/templates`;
+//! - the built-in templates in `resource.rs`.
+//!
+//! A template file is named after the template, with any extension, e.g. `segtree.cpp` is the template `segtree`.
+//! Snippets are stored in the same way, in the `snippets` directories.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use super::resource;
+
+/// The names of the built-in templates.
+pub const BUILTIN: &[&str] = &["default", "dp", "dp-2d", "empty"];
+
+/// Where a template comes from.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Origin {
+    Builtin,
+    Global,
+    Workspace,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Builtin => write!(f, "builtin"),
+            Origin::Global => write!(f, "global"),
+            Origin::Workspace => write!(f, "workspace"),
+        }
+    }
+}
+
+/// Get a built-in C++ template in the template scheme `cc_template`.
+pub fn builtin(name: &str, scheme: &str) -> Option<&'static str> {
+    Some(match name {
+        "dp" => match scheme {
+            "temp1" => resource::CPP_DP_TEMPLATE_0.trim_start(),
+            _ => resource::CPP_DP_TEMPLATE_1.trim_end(),
+        },
+        "default" => match scheme {
+            "temp1" => resource::CPP_TEMPLATE_1.trim_start(),
+            _ => resource::CPP_TEMPLATE_0.trim_start(),
+        },
+        "dp-2d" => match scheme {
+            "temp1" => resource::CPP_DP_2D_TEMPLATE_1.trim_start(),
+            _ => resource::CPP_DP_2D_TEMPLATE_0.trim_start(),
+        },
+        "empty" => "",
+        _ => return None,
+    })
+}
+
+/// Check if a template name can be used as a file name.
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("Invalid template name `{name}`. "));
+    }
+    Ok(())
+}
+
+/// The directories of templates or snippets.
+pub struct Registry {
+    /// The directories, in the order of lookup.
+    dirs: Vec<(Origin, PathBuf)>,
+}
+
+impl Registry {
+    /// Create the registry with the global configuration directory and the root of the workspace, if any.
+    /// `kind` is the name of the directories, e.g. `templates`.
+    pub fn new(global_config: &Option<String>, root: Option<&Path>, kind: &str) -> Self {
+        let mut dirs = Vec::new();
+        if let Some(root) = root {
+            dirs.push((Origin::Workspace, root.join(".oi_helper").join(kind)));
+        }
+        if let Some(global_config) = global_config {
+            dirs.push((Origin::Global, Path::new(global_config).join(kind)));
+        }
+        Self { dirs }
+    }
+
+    /// Get the directory of the templates from `origin`.
+    pub fn dir(&self, origin: Origin) -> Option<&Path> {
+        self.dirs.iter().find(|(o, _)| *o == origin).map(|(_, dir)| dir.as_path())
+    }
+
+    /// Find the file of the template `name` in a directory.
+    fn find_in(dir: &Path, name: &str) -> Option<PathBuf> {
+        let mut paths = fs::read_dir(dir).ok()?.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect::<Vec<PathBuf>>();
+        paths.sort();
+        paths.into_iter().find(|path| {
+            path.is_file() && (path.file_name().is_some_and(|n| n == name) || path.file_stem().is_some_and(|s| s == name))
+        })
+    }
+
+    /// Find the file of a user template.
+    pub fn find(&self, name: &str) -> Option<(Origin, PathBuf)> {
+        if check_name(name).is_err() {
+            return None;
+        }
+        self.dirs.iter().find_map(|(origin, dir)| Some((*origin, Self::find_in(dir, name)?)))
+    }
+
+    /// Find the file of a user template in the directory of `origin` only.
+    pub fn find_from(&self, origin: Origin, name: &str) -> Option<PathBuf> {
+        Self::find_in(self.dir(origin)?, name)
+    }
+
+    /// List all the templates and the built-in ones as `(name, origin, file)`, sorted by name.
+    /// The file is `None` for built-in templates.
+    /// Templates hidden by another one with the same name are also listed, after the one used.
+    pub fn list(&self, builtin: &[&str]) -> Vec<(String, Origin, Option<PathBuf>)> {
+        let mut result = Vec::new();
+        for (origin, dir) in &self.dirs {
+            let mut paths = match fs::read_dir(dir) {
+                Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect::<Vec<PathBuf>>(),
+                Err(_) => continue,
+            };
+            paths.sort();
+            for path in paths {
+                let name = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+                if path.is_file() && check_name(&name).is_ok() {
+                    result.push((name, *origin, Some(path)));
+                }
+            }
+        }
+        for name in builtin {
+            result.push((name.to_string(), Origin::Builtin, None));
+        }
+        // The sort is stable, so the templates of the same name stay in the order of lookup.
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+}
@@ -1,2 +1,7 @@
+pub mod datetime;
+pub mod hash;
+pub mod html;
+pub mod markdown;
 pub mod strdiff;
+pub mod table;
 pub mod web;
\ No newline at end of file
@@ -0,0 +1,40 @@
+//! This file contains helpers for dates, without depending on a date library.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Convert days since 1970-01-01 into `(year, month, day)`.
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Get the seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD` in UTC.
+pub fn format_date(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp / 86400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Get today's date as `YYYY-MM-DD` in UTC.
+pub fn today() -> String {
+    format_date(unix_now())
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM` in UTC.
+pub fn format_datetime(timestamp: u64) -> String {
+    let seconds = timestamp % 86400;
+    format!("{} {:02}:{:02}", format_date(timestamp), seconds / 3600, seconds % 3600 / 60)
+}
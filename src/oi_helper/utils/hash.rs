@@ -0,0 +1,17 @@
+//! This file contains a stable hash function, used for naming cache files.
+
+/// Calculate the 64-bit FNV-1a hash of the data.
+/// Unlike `std::hash`, the result is stable across platforms and Rust versions.
+pub fn fnv1a64(data: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325_u64;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Get the hash of the data as a hex string.
+pub fn hex_hash(data: &[u8]) -> String {
+    format!("{:016x}", fnv1a64(data))
+}
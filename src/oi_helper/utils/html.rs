@@ -0,0 +1,349 @@
+//! This file contains helpers for searching in HTML documents, including a small CSS selector matcher.
+
+use html_parser::{Element, Node};
+
+/// Decode all the named and numeric HTML entities. `&nbsp;` becomes a normal space.
+pub fn decode_entities(s: &str) -> String {
+    html_escape::decode_html_entities(s).replace('\u{a0}', " ")
+}
+
+/// Get the value of an attribute, or an empty string if it doesn't exist.
+pub fn attr<'a>(el: &'a Element, name: &str) -> &'a str {
+    match el.attributes.get(name) {
+        Some(Some(value)) => value.as_str(),
+        _ => "",
+    }
+}
+
+/// Check if the element has the class.
+pub fn has_class(el: &Element, class: &str) -> bool {
+    el.classes.iter().any(|c| c == class)
+}
+
+/// Get the text in the nodes. Line breaks are kept for `<br>` and block elements.
+pub fn text_content(nodes: &[Node]) -> String {
+    let mut result = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(text) => result.push_str(&decode_entities(text)),
+            Node::Element(el) => {
+                if el.name == "br" {
+                    result.push('\n');
+                } else {
+                    result.push_str(&text_content(&el.children));
+                    if matches!(el.name.as_str(), "div" | "p") && !result.ends_with('\n') {
+                        result.push('\n');
+                    }
+                }
+            }
+            Node::Comment(_) => {}
+        }
+    }
+    result
+}
+
+/// A compound selector, e.g. `pre.sample[data-id=1]:contains(Input)`.
+#[derive(Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    /// Attributes with an optional required value.
+    attributes: Vec<(String, Option<String>)>,
+    /// Texts that the content should contain.
+    contains: Vec<String>,
+    /// Texts that the content should start with, ignoring the leading whitespaces.
+    starts_with: Vec<String>,
+}
+
+impl Compound {
+    fn matches(&self, el: &Element) -> bool {
+        if let Some(tag) = &self.tag {
+            if !el.name.eq_ignore_ascii_case(tag) {
+                return false;
+            }
+        }
+        if self.id.is_some() && el.id != self.id {
+            return false;
+        }
+        if !self.classes.iter().all(|class| has_class(el, class)) {
+            return false;
+        }
+        for (name, value) in &self.attributes {
+            match (el.attributes.get(name), value) {
+                (None, _) => return false,
+                (Some(actual), Some(value)) if actual.as_deref().unwrap_or("") != value => return false,
+                _ => {}
+            }
+        }
+        if !self.contains.is_empty() || !self.starts_with.is_empty() {
+            let text = text_content(&el.children);
+            if !self.contains.iter().all(|s| text.contains(s.as_str())) {
+                return false;
+            }
+            if !self.starts_with.iter().all(|s| text.trim_start().starts_with(s.as_str())) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// How a compound selector relates to the previous one.
+#[derive(Clone, Copy, PartialEq)]
+enum Combinator {
+    /// `a b`
+    Descendant,
+    /// `a > b`
+    Child,
+    /// `a + b`
+    Adjacent,
+}
+
+/// A CSS selector. Supported syntax:
+/// - tag names, `.class`, `#id`, `[attr]` and `[attr=value]`;
+/// - the descendant (` `), child (`>`) and adjacent sibling (`+`) combinators;
+/// - `:contains(text)` and `:starts-with(text)`, which match the text content of the element.
+pub struct Selector {
+    parts: Vec<(Combinator, Compound)>,
+}
+
+impl Selector {
+    /// Parse a selector.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let chars = source.chars().collect::<Vec<char>>();
+        let mut parts = Vec::<(Combinator, Compound)>::new();
+        let mut current = Compound::default();
+        let mut current_empty = true;
+        let mut combinator = Combinator::Descendant;
+        let mut i = 0;
+
+        // Read an identifier starting at `i`.
+        let read_ident = |i: &mut usize| {
+            let start = *i;
+            while *i < chars.len() && (chars[*i].is_alphanumeric() || chars[*i] == '-' || chars[*i] == '_') {
+                *i += 1;
+            }
+            chars[start..*i].iter().collect::<String>()
+        };
+        // Read until the closing character, and remove the quotes.
+        let read_until = |i: &mut usize, close: char| -> Result<String, String> {
+            let start = *i;
+            while *i < chars.len() && chars[*i] != close {
+                *i += 1;
+            }
+            if *i >= chars.len() {
+                return Err(format!("missing `{close}` in selector `{source}`"));
+            }
+            let value = chars[start..*i].iter().collect::<String>();
+            *i += 1;
+            Ok(value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+        };
+
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                ' ' | '\t' | '\n' | '>' | '+' => {
+                    if !current_empty {
+                        parts.push((combinator, std::mem::take(&mut current)));
+                        current_empty = true;
+                        combinator = Combinator::Descendant;
+                    }
+                    if c == '>' {
+                        combinator = Combinator::Child;
+                    } else if c == '+' {
+                        combinator = Combinator::Adjacent;
+                    }
+                    i += 1;
+                }
+                '.' | '#' => {
+                    i += 1;
+                    let ident = read_ident(&mut i);
+                    if ident.is_empty() {
+                        return Err(format!("expected a name after `{c}` in selector `{source}`"));
+                    }
+                    if c == '.' {
+                        current.classes.push(ident);
+                    } else {
+                        current.id = Some(ident);
+                    }
+                    current_empty = false;
+                }
+                '[' => {
+                    i += 1;
+                    let content = read_until(&mut i, ']')?;
+                    match content.split_once('=') {
+                        Some((name, value)) => current.attributes.push((
+                            name.trim().to_string(),
+                            Some(value.trim().trim_matches(|c| c == '"' || c == '\'').to_string()),
+                        )),
+                        None => current.attributes.push((content, None)),
+                    }
+                    current_empty = false;
+                }
+                ':' => {
+                    i += 1;
+                    let pseudo = read_ident(&mut i);
+                    if i >= chars.len() || chars[i] != '(' {
+                        return Err(format!("expected `(` after `:{pseudo}` in selector `{source}`"));
+                    }
+                    i += 1;
+                    let argument = read_until(&mut i, ')')?;
+                    match pseudo.as_str() {
+                        "contains" => current.contains.push(argument),
+                        "starts-with" => current.starts_with.push(argument),
+                        _ => return Err(format!("unsupported pseudo-class `:{pseudo}` in selector `{source}`")),
+                    }
+                    current_empty = false;
+                }
+                '*' => {
+                    i += 1;
+                    current_empty = false;
+                }
+                _ => {
+                    let ident = read_ident(&mut i);
+                    if ident.is_empty() {
+                        return Err(format!("unexpected `{c}` in selector `{source}`"));
+                    }
+                    current.tag = Some(ident);
+                    current_empty = false;
+                }
+            }
+        }
+        if current_empty {
+            return Err(format!("selector `{source}` is empty or ends with a combinator"));
+        }
+        parts.push((combinator, current));
+        Ok(Self { parts })
+    }
+
+    /// Check if the element at the end of `path` matches the parts `..=k` of the selector.
+    /// Each level of `path` is the list of siblings and the index of the element in it.
+    fn matches_at(&self, k: usize, path: &[(&[Node], usize)]) -> bool {
+        let (siblings, index) = match path.last() {
+            Some(level) => *level,
+            None => return false,
+        };
+        let el = match siblings[index].element() {
+            Some(el) => el,
+            None => return false,
+        };
+        let (combinator, compound) = &self.parts[k];
+        if !compound.matches(el) {
+            return false;
+        }
+        if k == 0 {
+            return true;
+        }
+        let parent_path = &path[..path.len() - 1];
+        match combinator {
+            Combinator::Child => self.matches_at(k - 1, parent_path),
+            Combinator::Descendant => (1..=parent_path.len()).any(|len| self.matches_at(k - 1, &parent_path[..len])),
+            Combinator::Adjacent => {
+                // Find the previous element, skipping the texts and comments.
+                match (0..index).rev().find(|i| siblings[*i].element().is_some()) {
+                    Some(prev) => {
+                        let mut sibling_path = parent_path.to_vec();
+                        sibling_path.push((siblings, prev));
+                        self.matches_at(k - 1, &sibling_path)
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+
+    fn collect<'a>(&self, nodes: &'a [Node], path: &mut Vec<(&'a [Node], usize)>, result: &mut Vec<&'a Element>) {
+        for (index, node) in nodes.iter().enumerate() {
+            if let Some(el) = node.element() {
+                path.push((nodes, index));
+                if self.matches_at(self.parts.len() - 1, path) {
+                    result.push(el);
+                }
+                self.collect(&el.children, path, result);
+                path.pop();
+            }
+        }
+    }
+
+    /// Find all the elements matching the selector, in document order.
+    pub fn select<'a>(&self, nodes: &'a [Node]) -> Vec<&'a Element> {
+        let mut result = Vec::new();
+        self.collect(nodes, &mut Vec::new(), &mut result);
+        result
+    }
+}
+
+/// Find all the elements matching the selector. An invalid selector matches nothing.
+pub fn select<'a>(nodes: &'a [Node], selector: &str) -> Vec<&'a Element> {
+    match Selector::parse(selector) {
+        Ok(selector) => selector.select(nodes),
+        Err(err) => {
+            if crate::is_debug() {
+                println!("[DEBUG] Invalid selector: {}", err);
+            }
+            vec![]
+        }
+    }
+}
+
+/// Find the first element matching the selector.
+pub fn select_first<'a>(nodes: &'a [Node], selector: &str) -> Option<&'a Element> {
+    select(nodes, selector).into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use html_parser::Dom;
+
+    fn texts(nodes: &[Node], selector: &str) -> Vec<String> {
+        select(nodes, selector).into_iter().map(|el| text_content(&el.children)).collect()
+    }
+
+    #[test]
+    fn decode_named_and_numeric_entities() {
+        assert_eq!(decode_entities("a &lt; b &amp;&amp; c &gt; d"), "a < b && c > d");
+        assert_eq!(decode_entities("&quot;x&quot; &#39;y&#39;"), "\"x\" 'y'");
+        assert_eq!(decode_entities("&#60;&#x3E;&#x4E2D;&#25991;"), "<>中文");
+        assert_eq!(decode_entities("a&nbsp;b"), "a b");
+        assert_eq!(decode_entities("&unknown; & ;"), "&unknown; & ;");
+    }
+
+    #[test]
+    fn select_by_tag_class_id_and_attribute() {
+        let dom = Dom::parse(
+            r#"<div id="main"><p class="a b">1</p><p class="a">2</p><span data-id="3">3</span><span data-id="4">4</span></div>"#,
+        )
+        .unwrap();
+        assert_eq!(texts(&dom.children, "p"), vec!["1", "2"]);
+        assert_eq!(texts(&dom.children, "p.a.b"), vec!["1"]);
+        assert_eq!(texts(&dom.children, "#main > .a"), vec!["1", "2"]);
+        assert_eq!(texts(&dom.children, "span[data-id]"), vec!["3", "4"]);
+        assert_eq!(texts(&dom.children, "span[data-id=\"4\"]"), vec!["4"]);
+        assert!(select(&dom.children, "div#other p").is_empty());
+    }
+
+    #[test]
+    fn select_with_combinators() {
+        let dom = Dom::parse("<section><div><pre>1</pre></div><h3>In</h3><!-- x --><pre>2</pre><pre>3</pre></section>").unwrap();
+        assert_eq!(texts(&dom.children, "section pre"), vec!["1", "2", "3"]);
+        assert_eq!(texts(&dom.children, "section > pre"), vec!["2", "3"]);
+        assert_eq!(texts(&dom.children, "h3 + pre"), vec!["2"]);
+        assert_eq!(texts(&dom.children, "div + h3 + pre"), vec!["2"]);
+    }
+
+    #[test]
+    fn select_by_text() {
+        let dom = Dom::parse("<h3> Input #1</h3><pre>a</pre><h3>Sample Input</h3><pre>b</pre>").unwrap();
+        assert_eq!(texts(&dom.children, "h3:starts-with(Input) + pre"), vec!["a"]);
+        assert_eq!(texts(&dom.children, "h3:contains(Input) + pre"), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn invalid_selectors() {
+        for selector in ["", "p >", "p[id", ":has(p)", "p:contains", ".", "p $"] {
+            assert!(Selector::parse(selector).is_err(), "`{selector}` should be invalid");
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! This file contains a simple converter from HTML nodes to Markdown.
+
+use html_parser::{Element, Node};
+
+use super::html::{attr, decode_entities, has_class, text_content as raw_text};
+
+/// Append inline content. The parser drops the whitespaces around the text, so put a space back between words.
+fn push_inline(out: &mut String, s: &str) {
+    let needs_space = match (out.chars().last(), s.chars().next()) {
+        (Some(a), Some(b)) => {
+            (a.is_ascii_alphanumeric() || ",.;:!?)*$`".contains(a))
+                && (b.is_ascii_alphanumeric() || "([*$`!".contains(b))
+        }
+        _ => false,
+    };
+    if needs_space {
+        out.push(' ');
+    }
+    out.push_str(s);
+}
+
+/// Find the TeX source of a KaTeX-rendered formula.
+fn katex_source(el: &Element) -> Option<String> {
+    for child in &el.children {
+        if let Some(child) = child.element() {
+            if child.name == "annotation" && attr(child, "encoding") == "application/x-tex" {
+                return Some(raw_text(&child.children));
+            }
+            if let Some(source) = katex_source(child) {
+                return Some(source);
+            }
+        }
+    }
+    None
+}
+
+/// Convert the children of a list.
+fn convert_list(el: &Element, ordered: bool, out: &mut String) {
+    out.push_str("\n\n");
+    let mut counter = 1;
+    for child in &el.children {
+        if let Some(item) = child.element() {
+            if item.name != "li" {
+                continue;
+            }
+            let mut content = String::new();
+            convert_nodes(&item.children, &mut content);
+            if ordered {
+                out.push_str(&format!("{counter}. "));
+                counter += 1;
+            } else {
+                out.push_str("- ");
+            }
+            out.push_str(content.trim());
+            out.push('\n');
+        }
+    }
+    out.push('\n');
+}
+
+/// Convert a table into a Markdown table.
+fn convert_table(el: &Element, out: &mut String) {
+    let mut rows = Vec::<Vec<String>>::new();
+    collect_rows(el, &mut rows);
+    if rows.is_empty() {
+        return;
+    }
+    out.push_str("\n\n");
+    for (i, row) in rows.iter().enumerate() {
+        out.push_str(&format!("| {} |\n", row.join(" | ")));
+        if i == 0 {
+            out.push_str(&format!("|{}\n", " --- |".repeat(row.len())));
+        }
+    }
+    out.push('\n');
+}
+
+fn collect_rows(el: &Element, rows: &mut Vec<Vec<String>>) {
+    for child in &el.children {
+        if let Some(child) = child.element() {
+            if child.name == "tr" {
+                let mut row = Vec::new();
+                for cell in &child.children {
+                    if let Some(cell) = cell.element() {
+                        let mut content = String::new();
+                        convert_nodes(&cell.children, &mut content);
+                        row.push(content.trim().replace('\n', " "));
+                    }
+                }
+                rows.push(row);
+            } else {
+                collect_rows(child, rows);
+            }
+        }
+    }
+}
+
+/// Convert an element into Markdown.
+fn convert_element(el: &Element, out: &mut String) {
+    // Formulas rendered by KaTeX. Keep the TeX source.
+    if has_class(el, "katex") || has_class(el, "katex-display") {
+        if let Some(source) = katex_source(el) {
+            if has_class(el, "katex-display") {
+                out.push_str(&format!("\n\n$$\n{}\n$$\n\n", source.trim()));
+            } else {
+                push_inline(out, &format!("${}$", source.trim()));
+            }
+            return;
+        }
+    }
+
+    match el.name.as_str() {
+        "script" => {
+            // Formulas rendered by MathJax.
+            let kind = attr(el, "type");
+            if kind.starts_with("math/tex") {
+                let source = raw_text(&el.children);
+                if kind.contains("mode=display") {
+                    out.push_str(&format!("\n\n$$\n{}\n$$\n\n", source.trim()));
+                } else {
+                    push_inline(out, &format!("${}$", source.trim()));
+                }
+            }
+        }
+        "style" | "head" | "nav" | "footer" | "button" => {}
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = el.name[1..].parse::<usize>().unwrap_or(1);
+            let mut content = String::new();
+            convert_nodes(&el.children, &mut content);
+            out.push_str(&format!("\n\n{} {}\n\n", "#".repeat(level), content.trim()));
+        }
+        "br" => out.push_str("  \n"),
+        "hr" => out.push_str("\n\n---\n\n"),
+        "strong" | "b" => {
+            let mut content = String::new();
+            convert_nodes(&el.children, &mut content);
+            push_inline(out, &format!("**{}**", content.trim()));
+        }
+        "em" | "i" => {
+            let mut content = String::new();
+            convert_nodes(&el.children, &mut content);
+            push_inline(out, &format!("*{}*", content.trim()));
+        }
+        // AtCoder writes the formulas in <var>.
+        "var" => push_inline(out, &format!("${}$", raw_text(&el.children).trim())),
+        "code" => push_inline(out, &format!("`{}`", raw_text(&el.children))),
+        "pre" => {
+            let content = raw_text(&el.children);
+            out.push_str(&format!("\n\n```\n{}\n```\n\n", content.trim_end()));
+        }
+        "a" => {
+            let mut content = String::new();
+            convert_nodes(&el.children, &mut content);
+            let href = attr(el, "href");
+            if href.is_empty() {
+                push_inline(out, content.trim());
+            } else {
+                push_inline(out, &format!("[{}]({})", content.trim(), href));
+            }
+        }
+        "img" => push_inline(out, &format!("![{}]({})", attr(el, "alt"), attr(el, "src"))),
+        "ul" => convert_list(el, false, out),
+        "ol" => convert_list(el, true, out),
+        "table" => convert_table(el, out),
+        "p" | "div" | "section" | "article" | "blockquote" | "body" | "html" | "main" => {
+            out.push_str("\n\n");
+            convert_nodes(&el.children, out);
+            out.push_str("\n\n");
+        }
+        _ => convert_nodes(&el.children, out),
+    }
+}
+
+fn convert_nodes(nodes: &[Node], out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => {
+                // Collapse the whitespaces like browsers do.
+                let text = decode_entities(text);
+                push_inline(out, &text.split_whitespace().collect::<Vec<&str>>().join(" "));
+            }
+            Node::Element(el) => convert_element(el, out),
+            Node::Comment(_) => {}
+        }
+    }
+}
+
+/// Convert the HTML nodes into Markdown.
+pub fn html_to_markdown(nodes: &[Node]) -> String {
+    let mut out = String::new();
+    convert_nodes(nodes, &mut out);
+
+    // Remove the redundant blank lines.
+    let mut result = String::new();
+    let mut blank_lines = 0;
+    let mut in_code = false;
+    for line in out.lines() {
+        // Keep everything in code blocks as is.
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        } else if in_code {
+            result.push_str(line);
+            result.push('\n');
+            continue;
+        }
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            if blank_lines > 1 {
+                continue;
+            }
+            result.push('\n');
+        } else {
+            blank_lines = 0;
+            result.push_str(line.trim_end_matches(' ').trim_start());
+            if line.ends_with("  ") {
+                result.push_str("  ");
+            }
+            result.push('\n');
+        }
+    }
+    result.trim().to_string()
+}
@@ -64,3 +64,70 @@ pub fn colored_diff(original: &str, target: &str) -> (Vec<StyledContent<String>>
     // Return the results.
     (result_original, result_target)
 }
+
+/// A line of a line-by-line diff.
+pub enum LineDiff<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Compare two texts line by line, keeping their longest common subsequence of lines.
+pub fn line_diff<'a>(original: &'a str, target: &'a str) -> Vec<LineDiff<'a>> {
+    let original = original.lines().collect::<Vec<&str>>();
+    let target = target.lines().collect::<Vec<&str>>();
+
+    // lcs[i][j] is the length of the longest common subsequence of original[i..] and target[j..].
+    let mut lcs = vec![vec![0_usize; target.len() + 1]; original.len() + 1];
+    for i in (0..original.len()).rev() {
+        for j in (0..target.len()).rev() {
+            lcs[i][j] = if original[i] == target[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < original.len() || j < target.len() {
+        if i < original.len() && j < target.len() && original[i] == target[j] {
+            result.push(LineDiff::Same(original[i]));
+            i += 1;
+            j += 1;
+        } else if i < original.len() && (j == target.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            result.push(LineDiff::Removed(original[i]));
+            i += 1;
+        } else {
+            result.push(LineDiff::Added(target[j]));
+            j += 1;
+        }
+    }
+    result
+}
+
+/// Print a line diff like `diff -u`, with `context` unchanged lines around the changes.
+pub fn print_line_diff(diff: &[LineDiff], context: usize) {
+    let changed = diff.iter().map(|line| !matches!(line, LineDiff::Same(_))).collect::<Vec<bool>>();
+    let mut last_printed = None;
+    let mut line_number = 0;
+    for (i, line) in diff.iter().enumerate() {
+        if !matches!(line, LineDiff::Removed(_)) {
+            line_number += 1;
+        }
+        let near_change = changed[i.saturating_sub(context)..(i + context + 1).min(diff.len())].iter().any(|c| *c);
+        if !near_change {
+            continue;
+        }
+        if last_printed.is_none_or(|last| last + 1 != i) {
+            println!("{}", format!("@@ line {} @@", line_number.max(1)).dark_grey());
+        }
+        last_printed = Some(i);
+        match line {
+            LineDiff::Same(line) => println!("  {}", line),
+            LineDiff::Removed(line) => println!("{}", format!("- {}", line).red()),
+            LineDiff::Added(line) => println!("{}", format!("+ {}", line).green()),
+        }
+    }
+}
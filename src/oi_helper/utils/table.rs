@@ -0,0 +1,52 @@
+//! This file contains a simple table for the terminal, with the columns aligned.
+
+use crossterm::style::Stylize;
+
+pub struct Table {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+/// Get how many columns a string takes in the terminal. CJK characters take two columns.
+fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+impl Table {
+    pub fn new(header: &[&str]) -> Self {
+        Self { header: header.iter().map(|s| s.to_string()).collect(), rows: Vec::new() }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Print the table. The first column is aligned to the left, and the others to the right.
+    pub fn print(&self) {
+        let mut widths = self.header.iter().map(|s| display_width(s)).collect::<Vec<usize>>();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                if i < widths.len() {
+                    widths[i] = widths[i].max(display_width(cell));
+                }
+            }
+        }
+        let format_row = |row: &[String]| {
+            let cells = row.iter().zip(&widths).enumerate().map(|(i, (cell, width))| {
+                let padding = " ".repeat(width - display_width(cell));
+                if i == 0 { format!("{cell}{padding}") } else { format!("{padding}{cell}") }
+            });
+            cells.collect::<Vec<String>>().join("  ")
+        };
+        println!("{}", format_row(&self.header).bold());
+        println!("{}", "-".repeat(widths.iter().sum::<usize>() + 2 * widths.len().saturating_sub(1)).dark_grey());
+        for row in &self.rows {
+            println!("{}", format_row(row));
+        }
+    }
+}
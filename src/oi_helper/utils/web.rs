@@ -1,151 +1,348 @@
-use std::io::Read;
+//! This file contains utilities for fetching problems from online judges.
 
-use html_parser::{Dom, Node};
+use std::{
+    fs,
+    path::PathBuf,
+    sync::OnceLock,
+    time::{Duration, SystemTime},
+};
 
+use json::{object, JsonValue};
 
-pub fn get_remotely(url: &str) -> anyhow::Result<String> {
-    let mut res = reqwest::blocking::get(url)?;
-    let mut body = String::new();
-    res.read_to_string(&mut body)?;
-    if crate::is_debug() {
-        println!("Got content from {}", url);
-        println!("Status: {}", res.status());
-        println!("Headers: \n{:#?}", res.headers());
-        println!("Body: \n{}", body);
+use super::hash::hex_hash;
+
+pub mod atcoder;
+pub mod codeforces;
+pub mod luogu;
+
+/// A problem fetched from an online judge.
+pub struct FetchedProblem {
+    /// The online judge, e.g. `luogu`.
+    pub oj: String,
+    pub problem_id: String,
+    pub url: String,
+    pub title: String,
+    /// The time limit in milliseconds.
+    pub time_limit: u32,
+    /// The memory limit in megabytes.
+    pub memory_limit: u32,
+    pub difficulty: Option<String>,
+    pub tags: Vec<String>,
+    /// The example test cases as (input, output) pairs.
+    pub samples: Vec<(String, String)>,
+    /// The sections of the statement as (heading, Markdown content) pairs.
+    pub statement: Vec<(String, String)>,
+}
+
+impl FetchedProblem {
+    /// The metadata of the problem, which will be saved as `problem_info.json`.
+    pub fn metadata(&self) -> JsonValue {
+        object! {
+            "oj": self.oj.clone(),
+            "problem_id": self.problem_id.clone(),
+            "url": self.url.clone(),
+            "title": self.title.clone(),
+            "difficulty": self.difficulty.clone(),
+            "tags": self.tags.clone(),
+            "time_limit": self.time_limit,
+            "memory_limit": self.memory_limit,
+        }
     }
-    Ok(body)
+}
+/// A problem in a contest or a problem list.
+pub struct ProblemEntry {
+    /// The name of the source file without extension, e.g. `A`.
+    pub name: String,
+    /// The problem specifier which can be passed to `fetch_problem`, e.g. `cf:1850/A`.
+    pub problem: String,
+}
+
+/// Fetch a problem with a specifier like `luogu:P1001`, `cf:1850/A` or `atc:abc300/abc300_a`.
+pub fn fetch_problem(spec: &str) -> anyhow::Result<FetchedProblem> {
+    let (oj, problem_id) = match spec.split_once(':') {
+        Some(pair) => pair,
+        None => anyhow::bail!("Invalid problem {spec}, it should look like `luogu:P1001`. "),
+    };
+    match oj {
+        "luogu" | "lg" => luogu::get_problem(problem_id),
+        "codeforces" | "cf" => match problem_id.split_once('/') {
+            Some((contest, index)) => codeforces::get_problem(contest, index),
+            None => anyhow::bail!("Invalid Codeforces problem {problem_id}, it should look like `1850/A`. "),
+        },
+        "atcoder" | "atc" => match problem_id.split_once('/') {
+            Some((contest, task)) => atcoder::get_problem(contest, task),
+            None => match problem_id.rsplit_once('_') {
+                Some((contest, _)) => atcoder::get_problem(contest, problem_id),
+                None => anyhow::bail!("Invalid AtCoder problem {problem_id}, it should look like `abc300/abc300_a`. "),
+            },
+        },
+        _ => anyhow::bail!("Unsupported online judge: {oj}"),
+    }
+}
+
+/// Fetch the problems of a contest or a problem list with a specifier like `cf:1850`, `atc:abc300`,
+/// `luogu-training:100` or `luogu-contest:12345`.
+pub fn fetch_problem_list(spec: &str) -> anyhow::Result<Vec<ProblemEntry>> {
+    let (oj, list_id) = match spec.split_once(':') {
+        Some(pair) => pair,
+        None => anyhow::bail!("Invalid problem list {spec}, it should look like `cf:1850`. "),
+    };
+    match oj {
+        "codeforces" | "cf" => codeforces::get_contest_problems(list_id),
+        "atcoder" | "atc" => atcoder::get_contest_problems(list_id),
+        "luogu-training" | "lg-training" => luogu::get_training_problems(list_id),
+        "luogu-contest" | "lg-contest" => luogu::get_contest_problems(list_id),
+        _ => anyhow::bail!("Unsupported problem list: {oj}"),
+    }
+}
+
+/// Pair the sample inputs with the outputs. It's an error if their numbers differ, since the samples couldn't be paired correctly.
+pub fn pair_samples(inputs: Vec<String>, outputs: Vec<String>) -> anyhow::Result<Vec<(String, String)>> {
+    if inputs.len() != outputs.len() {
+        anyhow::bail!("Found {} sample input(s) but {} sample output(s) in the page. ", inputs.len(), outputs.len());
+    }
+    Ok(inputs.into_iter().zip(outputs).collect())
+}
+
+/// Parse a time limit like `2 seconds`, `1.5 sec` or `1000ms` into milliseconds.
+pub fn parse_time_limit(text: &str) -> Option<u32> {
+    let (value, unit) = split_number(text)?;
+    let unit = unit.to_lowercase();
+    if unit.starts_with("ms") || unit.starts_with("millisecond") {
+        Some(value as u32)
+    } else if unit.starts_with('s') {
+        Some((value * 1000.0).round() as u32)
+    } else {
+        None
+    }
+}
+
+/// Parse a memory limit like `256 megabytes` or `1024 MB` into megabytes.
+pub fn parse_memory_limit(text: &str) -> Option<u32> {
+    let (value, unit) = split_number(text)?;
+    let unit = unit.to_lowercase();
+    if unit.starts_with('m') {
+        Some(value as u32)
+    } else if unit.starts_with('g') {
+        Some((value * 1024.0) as u32)
+    } else if unit.starts_with('k') {
+        Some((value / 1024.0).ceil() as u32)
+    } else {
+        None
+    }
+}
+
+/// Find the first number in the text, and return it along with the text after it.
+fn split_number(text: &str) -> Option<(f64, &str)> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let rest = &text[start..];
+    let len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+    let value = rest[..len].parse().ok()?;
+    Some((value, rest[len..].trim_start()))
+}
+
+/// Options of the web fetcher, read from the global configuration.
+pub struct WebOptions {
+    /// The timeout of each request in seconds.
+    pub timeout: u64,
+    /// How many times a failed request will be retried.
+    pub retries: u32,
+    /// The proxy for all requests. Empty means no proxy.
+    pub proxy: String,
+    pub user_agent: String,
+    /// Where the responses are cached. `None` disables the cache.
+    pub cache_dir: Option<PathBuf>,
+    /// How long (in seconds) a cached response is considered fresh. 0 means always fetch again.
+    pub cache_ttl: u64,
+    /// Only serve from the cache.
+    pub offline: bool,
+}
+
+impl Default for WebOptions {
+    fn default() -> Self {
+        Self {
+            timeout: 10,
+            retries: 2,
+            proxy: String::new(),
+            user_agent: format!("oi_helper/{}", crate::VERSION),
+            cache_dir: None,
+            cache_ttl: 86400,
+            offline: false,
+        }
+    }
+}
+
+static WEB_OPTIONS: OnceLock<WebOptions> = OnceLock::new();
+
+/// Set the options of the web fetcher. Only the first call takes effect.
+pub fn configure(options: WebOptions) {
+    let _ = WEB_OPTIONS.set(options);
 }
 
-pub fn get_luogu_problem_content(problem_id: &str) -> anyhow::Result<Dom> {
-    let content = get_remotely(&format!("https://www.luogu.com.cn/problem/{}", problem_id))?;
-    let dom_tree = Dom::parse(&content)?;
-    Ok(dom_tree)
-}
-
-#[allow(unused_doc_comments)]
-/// Search from the Node n.
-fn search_from(n: &Node) -> Option<Vec<(String, String)>> {
-
-    // Store the test cases.
-    let mut test_cases = Vec::<(String, String)>::new();
-
-    // Check if the n is an element,
-    match n.element() {
-
-        Some(el) => {
-
-            // Started to parse.
-            let mut buffer = (String::new(), String::new());
-
-            /**
-             * 0 means normal.
-             * 1 means reading the test-in.
-             * 2 means reading the test-out.
-             */
-            let mut next_type = 0;
-
-            for i in &el.children {
-
-                match next_type {
-                    // Check each element
-
-                    // Normal mode
-                    0 => {
-                        match i.element() {
-                            Some(el) => {
-                                // Check if it's before a input test-case.
-                                if el.name == "h3" && el.children.len() != 0 && el.children[0].text().is_some() && el.children[0].text().unwrap().starts_with("输入样例") {
-                                    next_type = 1;
-                                    continue;
-                                }
-                                // Check if it's before a output test-case.
-                                if el.name == "h3" && el.children.len() != 0 && el.children[0].text().is_some() && el.children[0].text().unwrap().starts_with("输出样例") {
-                                    next_type = 2;
-                                    continue;
-                                }
-
-                                // Otherwise, search its children, and merge the result.
-                                if let Some(cases) = search_from(i) {
-                                    for i in cases {
-                                        test_cases.push(i);
-                                    }
-                                }
-                            }
-                            None => {}
-                        }
-                    }
-
-                    // Reading in-case
-                    1 => {
-                        match i.element() {
-                            // Checking the relationships between them.
-                            // There SHOULD be no problem.
-                            Some(el) => {
-                                if el.name == "pre" && el.children.len() != 0 {
-                                    if let Some(c) = el.children[0].element() {
-                                        if c.name == "code" {
-                                            if let Some(s) = el.children[0].element().unwrap().children[0].text() {
-                                                buffer.0 = String::from(s.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&"));
-                                            }
-                                        }
-                                    }
-                                }
-                                next_type = 0;
-                            }
-                            None => {
-                                next_type = 0;
-                            }
-                        }
-                    }
-
-                    // Read the out-case
-                    2 => {
-                        // The same as 1.
-                        match i.element() {
-                            Some(el) => {
-                                if el.name == "pre" && el.children.len() != 0 {
-                                    if let Some(c) = el.children[0].element() {
-                                        if c.name == "code" {
-                                            if let Some(s) = el.children[0].element().unwrap().children[0].text() {
-                                                // Update it.
-                                                buffer.1 = String::from(s.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&"));
-                                                test_cases.push(buffer);
-                                                buffer = (String::new(), String::new());
-                                            }
-                                        }
-                                    }
-                                }
-                                next_type = 0;
-                            }
-                            None => {
-                                next_type = 0;
-                            }
-                        }
-                    }
-
-                    _ => {}
+fn options() -> &'static WebOptions {
+    WEB_OPTIONS.get_or_init(WebOptions::default)
+}
+
+/// Get the path to the cache file of the url.
+fn cache_path(url: &str) -> Option<PathBuf> {
+    options()
+        .cache_dir
+        .as_ref()
+        .map(|dir| dir.join(format!("{}.cache", hex_hash(url.as_bytes()))))
+}
+
+/// Read the cached response of the url, along with its age in seconds.
+fn read_cache(url: &str) -> Option<(Vec<u8>, u64)> {
+    let path = cache_path(url)?;
+    let content = fs::read(&path).ok()?;
+    let age = fs::metadata(&path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age.as_secs())
+        .unwrap_or(u64::MAX);
+    Some((content, age))
+}
+
+fn write_cache(url: &str, content: &[u8]) {
+    if let Some(path) = cache_path(url) {
+        let result = path
+            .parent()
+            .map(fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| fs::write(&path, content));
+        if let Err(err) = result {
+            eprintln!("Cannot write the cache of {}: {}", url, err);
+        }
+    }
+}
+
+/// Send the request, retrying when it fails.
+fn request(url: &str) -> anyhow::Result<Vec<u8>> {
+    let opts = options();
+    let mut builder = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(opts.timeout))
+        .user_agent(opts.user_agent.as_str());
+    if !opts.proxy.is_empty() {
+        builder = builder.proxy(reqwest::Proxy::all(opts.proxy.as_str())?);
+    }
+    let client = builder.build()?;
+
+    let mut tries = 0;
+    loop {
+        let result = client
+            .get(url)
+            .send()
+            .and_then(|res| res.error_for_status())
+            .and_then(|res| {
+                if crate::is_debug() {
+                    println!("Got content from {}", url);
+                    println!("Status: {}", res.status());
+                    println!("Headers: \n{:#?}", res.headers());
+                }
+                res.bytes()
+            });
+        match result {
+            Ok(body) => return Ok(body.to_vec()),
+            Err(err) if tries < opts.retries => {
+                tries += 1;
+                eprintln!("Failed to fetch {}: {}. Retrying ({}/{})... ", url, err, tries, opts.retries);
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Get the content of the url, from the cache if possible.
+pub fn get_remotely_bytes(url: &str) -> anyhow::Result<Vec<u8>> {
+    let opts = options();
+    let cached = read_cache(url);
+
+    if opts.offline {
+        return match cached {
+            Some((content, _)) => {
+                if crate::is_debug() {
+                    println!("[DEBUG] Cache hit (offline): {}", url);
                 }
+                Ok(content)
+            }
+            None => anyhow::bail!("{} is not in the cache, cannot fetch it in offline mode. ", url),
+        };
+    }
 
+    if let Some((content, age)) = &cached {
+        if age < &opts.cache_ttl {
+            if crate::is_debug() {
+                println!("[DEBUG] Cache hit ({}s old): {}", age, url);
             }
+            return Ok(content.clone());
         }
+    }
+    if crate::is_debug() {
+        println!("[DEBUG] Cache miss: {}", url);
+    }
 
-        None => {
-            return None;
+    match request(url) {
+        Ok(content) => {
+            write_cache(url, &content);
+            Ok(content)
         }
+        Err(err) => match cached {
+            // Better an outdated response than nothing.
+            Some((content, _)) => {
+                eprintln!("Failed to fetch {}: {}. Using the cached content. ", url, err);
+                Ok(content)
+            }
+            None => Err(err),
+        },
+    }
+}
 
+pub fn get_remotely(url: &str) -> anyhow::Result<String> {
+    let body = String::from_utf8_lossy(&get_remotely_bytes(url)?).to_string();
+    if crate::is_debug() {
+        println!("Body: \n{}", body);
     }
+    Ok(body)
+}
 
-    Some(test_cases)
+/// Resolve a link in the page at `base`.
+pub fn resolve_url(base: &str, link: &str) -> String {
+    if link.starts_with("http://") || link.starts_with("https://") {
+        return link.to_string();
+    }
+    let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+    if let Some(link) = link.strip_prefix("//") {
+        return format!("{scheme}://{link}");
+    }
+    let host = rest.split('/').next().unwrap_or("");
+    if link.starts_with('/') {
+        format!("{scheme}://{host}{link}")
+    } else {
+        let dir = match base.rfind('/') {
+            Some(pos) if pos > scheme.len() + 2 => &base[..pos],
+            _ => base,
+        };
+        format!("{dir}/{link}")
+    }
 }
 
-pub fn get_test_case_from_luogu_tree(dom: &Dom) -> Vec::<(String, String)> {
-    let mut test_cases = Vec::<(String, String)>::new();
-    for i in &dom.children {
-        if let Some(cases) = search_from(i) {
-            for i in cases {
-                test_cases.push(i);
+
+/// Decode a percent-encoded string like `decodeURIComponent` in JavaScript.
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(Ok(b)) = s.get(i + 1..i + 3).map(|hex| u8::from_str_radix(hex, 16)) {
+                result.push(b);
+                i += 3;
+                continue;
             }
         }
+        result.push(bytes[i]);
+        i += 1;
     }
-    test_cases
+    String::from_utf8_lossy(&result).to_string()
 }
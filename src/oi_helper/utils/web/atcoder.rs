@@ -0,0 +1,99 @@
+//! This file contains functions for fetching problems from AtCoder.
+
+use html_parser::{Dom, Node};
+
+use crate::oi_helper::utils::{
+    html::{attr, select, select_first, text_content},
+    markdown::html_to_markdown,
+};
+
+use super::{get_remotely, pair_samples, parse_memory_limit, parse_time_limit, FetchedProblem, ProblemEntry};
+
+/// Get the tasks of a contest from its task list, e.g. `get_contest_problems("abc300")`.
+pub fn get_contest_problems(contest_id: &str) -> anyhow::Result<Vec<ProblemEntry>> {
+    let url = format!("https://atcoder.jp/contests/{}/tasks", contest_id);
+    let dom = Dom::parse(&get_remotely(&url)?)?;
+    let mut result = Vec::new();
+    for row in select(&dom.children, "tbody > tr") {
+        // The first column links to the task, with the task index as its text.
+        if let Some(link) = select_first(&row.children, "td a") {
+            let task = attr(link, "href").rsplit('/').next().unwrap_or("");
+            if task.is_empty() {
+                continue;
+            }
+            result.push(ProblemEntry {
+                name: text_content(&link.children).trim().to_string(),
+                problem: format!("atc:{}/{}", contest_id, task),
+            });
+        }
+    }
+    if result.is_empty() {
+        anyhow::bail!("Cannot find any task in {url}");
+    }
+    Ok(result)
+}
+
+/// Fetch a task, e.g. `get_problem("abc300", "abc300_a")`.
+pub fn get_problem(contest_id: &str, task: &str) -> anyhow::Result<FetchedProblem> {
+    let url = format!("https://atcoder.jp/contests/{}/tasks/{}", contest_id, task);
+    let dom = Dom::parse(&get_remotely(&url)?)?;
+
+    // The title is the first text in `span.h2`, followed by a link to the editorial.
+    let title = select_first(&dom.children, "span.h2")
+        .and_then(|el| el.children.iter().find_map(|node| node.text()))
+        .map(|text| text.trim().to_string())
+        .unwrap_or_else(|| task.to_string());
+
+    // The limits look like `Time Limit: 2 sec / Memory Limit: 1024 MB`.
+    let limits = select_first(&dom.children, "p:contains(Time Limit)")
+        .map(|el| text_content(&el.children))
+        .unwrap_or_default();
+    let (time_text, memory_text) = limits.split_once('/').unwrap_or((&limits, ""));
+    let time_limit = parse_time_limit(time_text).unwrap_or(1000);
+    let memory_limit = parse_memory_limit(memory_text).unwrap_or(256);
+
+    // Prefer the English statement.
+    let root: &[Node] = match select_first(&dom.children, "span.lang-en") {
+        Some(el) => &el.children,
+        None => &dom.children,
+    };
+
+    let mut inputs = select(root, "h3:starts-with(Sample Input) + pre");
+    let mut outputs = select(root, "h3:starts-with(Sample Output) + pre");
+    if inputs.is_empty() {
+        inputs = select(root, "h3:starts-with(入力例) + pre");
+        outputs = select(root, "h3:starts-with(出力例) + pre");
+    }
+    let samples = pair_samples(
+        inputs.iter().map(|input| text_content(&input.children)).collect(),
+        outputs.iter().map(|output| text_content(&output.children)).collect(),
+    )?;
+
+    // Each `div.part > section` is a section of the statement.
+    let mut statement = Vec::new();
+    for section in select(root, "div.part > section") {
+        let heading = select_first(&section.children, "h3")
+            .map(|el| text_content(&el.children).trim().to_string())
+            .unwrap_or_default();
+        let content = section
+            .children
+            .iter()
+            .filter(|node| node.element().map(|el| el.name != "h3").unwrap_or(true))
+            .cloned()
+            .collect::<Vec<Node>>();
+        statement.push((heading, html_to_markdown(&content)));
+    }
+
+    Ok(FetchedProblem {
+        oj: String::from("atcoder"),
+        problem_id: task.to_string(),
+        url,
+        title,
+        time_limit,
+        memory_limit,
+        difficulty: None,
+        tags: vec![],
+        samples,
+        statement,
+    })
+}
@@ -0,0 +1,100 @@
+//! This file contains functions for fetching problems from Codeforces.
+
+use html_parser::{Dom, Element, Node};
+
+use crate::oi_helper::utils::{
+    html::{has_class, select, select_first, text_content},
+    markdown::html_to_markdown,
+};
+
+use super::{get_remotely, pair_samples, parse_memory_limit, parse_time_limit, FetchedProblem, ProblemEntry};
+
+/// Get the problems of a contest through the Codeforces API.
+pub fn get_contest_problems(contest_id: &str) -> anyhow::Result<Vec<ProblemEntry>> {
+    let content = get_remotely(&format!(
+        "https://codeforces.com/api/contest.standings?contestId={}&from=1&count=1",
+        contest_id
+    ))?;
+    let response = json::parse(&content)?;
+    if response["status"].as_str() != Some("OK") {
+        anyhow::bail!(
+            "Codeforces API failed: {}",
+            response["comment"].as_str().unwrap_or("unknown error")
+        );
+    }
+    Ok(response["result"]["problems"]
+        .members()
+        .filter_map(|problem| problem["index"].as_str())
+        .map(|index| ProblemEntry {
+            name: index.to_string(),
+            problem: format!("cf:{}/{}", contest_id, index),
+        })
+        .collect())
+}
+
+/// Get the text of the first element matching the selector.
+fn text_of(nodes: &[Node], selector: &str) -> Option<String> {
+    select_first(nodes, selector).map(|el| text_content(&el.children))
+}
+
+/// Convert the sections of the statement, e.g. `Input`, `Output` and `Note`, into Markdown.
+fn statement_sections(statement: &Element) -> Vec<(String, String)> {
+    let mut sections = Vec::new();
+    for child in &statement.children {
+        let el = match child.element() {
+            Some(el) if !has_class(el, "header") => el,
+            _ => continue,
+        };
+        let heading = text_of(&el.children, ".section-title").unwrap_or_default();
+        let content = el
+            .children
+            .iter()
+            .filter(|node| !node.element().map(|el| has_class(el, "section-title")).unwrap_or(false))
+            .cloned()
+            .collect::<Vec<Node>>();
+        // Codeforces writes the formulas as $$$...$$$.
+        let markdown = html_to_markdown(&content).replace("$$$$$$", "$$").replace("$$$", "$");
+        sections.push((heading.trim().to_string(), markdown));
+    }
+    sections
+}
+
+/// Fetch a problem, e.g. `get_problem("4", "A")`.
+pub fn get_problem(contest_id: &str, index: &str) -> anyhow::Result<FetchedProblem> {
+    let url = format!("https://codeforces.com/contest/{}/problem/{}", contest_id, index);
+    let dom = Dom::parse(&get_remotely(&url)?)?;
+    let statement = match select_first(&dom.children, "div.problem-statement") {
+        Some(el) => el,
+        None => anyhow::bail!("Cannot find the problem statement in {url}"),
+    };
+
+    let title = text_of(&statement.children, ".header > .title").unwrap_or_else(|| index.to_string());
+    let time_limit = text_of(&statement.children, ".time-limit")
+        .and_then(|text| parse_time_limit(&text))
+        .unwrap_or(1000);
+    let memory_limit = text_of(&statement.children, ".memory-limit")
+        .and_then(|text| parse_memory_limit(&text))
+        .unwrap_or(256);
+
+    // Each `div.sample-test` contains pairs of `div.input > pre` and `div.output > pre`.
+    let inputs = select(&statement.children, ".sample-test .input > pre");
+    let outputs = select(&statement.children, ".sample-test .output > pre");
+    let text_of_sample = |el: &&Element| text_content(&el.children).trim().to_string() + "\n";
+    let samples = pair_samples(inputs.iter().map(text_of_sample).collect(), outputs.iter().map(text_of_sample).collect())?;
+
+    Ok(FetchedProblem {
+        oj: String::from("codeforces"),
+        problem_id: format!("{}{}", contest_id, index),
+        url,
+        title: title.trim().to_string(),
+        time_limit,
+        memory_limit,
+        difficulty: None,
+        tags: select(&dom.children, ".tag-box")
+            .into_iter()
+            .map(|el| text_content(&el.children).trim().to_string())
+            .collect(),
+        samples,
+        statement: statement_sections(statement),
+    })
+}
@@ -0,0 +1,358 @@
+//! This file contains functions for fetching problems from Luogu.
+
+use html_parser::Dom;
+use json::JsonValue;
+
+use crate::oi_helper::utils::{
+    html::{select, select_first, text_content},
+    markdown::html_to_markdown,
+};
+
+use super::{get_remotely, pair_samples, percent_decode, FetchedProblem, ProblemEntry};
+
+/// The names of Luogu's difficulty levels, indexed by the difficulty value.
+static DIFFICULTIES: [&str; 8] = [
+    "暂无评定",
+    "入门",
+    "普及−",
+    "普及/提高−",
+    "普及+/提高",
+    "提高+/省选−",
+    "省选/NOI−",
+    "NOI/NOI+/CTSC",
+];
+
+/// Fetch a problem from Luogu with the given problem id.
+pub fn get_problem(problem_id: &str) -> anyhow::Result<FetchedProblem> {
+    let url = format!("https://www.luogu.com.cn/problem/{}", problem_id);
+    let content = get_remotely(&url)?;
+
+    // Prefer the data embedded in the page, which contains the limits and other metadata.
+    if let Some(data) = extract_problem_data(&content) {
+        return Ok(problem_from_data(problem_id, &url, &data));
+    }
+
+    // Fall back to searching the samples in the document.
+    eprintln!("Cannot find the problem data in the page, using the default limits (1000ms, 256MB). ");
+    let dom_tree = Dom::parse(&content)?;
+    Ok(FetchedProblem {
+        oj: String::from("luogu"),
+        problem_id: problem_id.to_string(),
+        url,
+        title: problem_id.to_string(),
+        time_limit: 1000,
+        memory_limit: 256,
+        difficulty: None,
+        tags: vec![],
+        samples: get_test_case_from_luogu_tree(&dom_tree)?,
+        statement: statement_from_dom(&dom_tree),
+    })
+}
+
+/// Convert the statement in the document into Markdown.
+fn statement_from_dom(dom: &Dom) -> Vec<(String, String)> {
+    let content = match select_first(&dom.children, "article").or_else(|| select_first(&dom.children, "body")) {
+        Some(el) => html_to_markdown(&el.children),
+        None => html_to_markdown(&dom.children),
+    };
+    vec![(String::new(), content)]
+}
+
+/// Build the statement from Luogu's problem object, whose content is already Markdown.
+fn statement_from_data(data: &JsonValue, samples: &[(String, String)]) -> Vec<(String, String)> {
+    // The newer layout keeps the statement in a separate object.
+    let content = if data["content"].is_object() {
+        &data["content"]
+    } else if data["contenu"].is_object() {
+        &data["contenu"]
+    } else {
+        data
+    };
+    let get = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| content[*key].as_str())
+            .unwrap_or("")
+            .trim()
+            .to_string()
+    };
+
+    let mut sample_section = String::new();
+    for (i, (input, output)) in samples.iter().enumerate() {
+        sample_section.push_str(&format!(
+            "### 输入样例 #{}\n\n```\n{}\n```\n\n### 输出样例 #{}\n\n```\n{}\n```\n\n",
+            i + 1,
+            input.trim_end(),
+            i + 1,
+            output.trim_end()
+        ));
+    }
+
+    let sections = vec![
+        (String::from("题目背景"), get(&["background"])),
+        (String::from("题目描述"), get(&["description"])),
+        (String::from("输入格式"), get(&["inputFormat", "formatI"])),
+        (String::from("输出格式"), get(&["outputFormat", "formatO"])),
+        (String::from("输入输出样例"), sample_section.trim().to_string()),
+        (String::from("说明/提示"), get(&["hint"])),
+    ];
+    sections.into_iter().filter(|(_, body)| !body.is_empty()).collect()
+}
+
+/// Get the text between `start` and `end` after the first occurrence of `start`.
+fn between<'a>(s: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let begin = s.find(start)? + start.len();
+    let len = s[begin..].find(end)?;
+    Some(&s[begin..begin + len])
+}
+
+/// Extract the JSON data embedded in a Luogu page.
+/// Both the `lentille-context` script and the older `window._feInjection` are supported.
+pub fn extract_page_data(page: &str) -> Option<JsonValue> {
+    // The newer layout: <script id="lentille-context" type="application/json">{...}</script>
+    if let Some(pos) = page.find("id=\"lentille-context\"") {
+        if let Some(raw) = between(&page[pos..], ">", "</script>") {
+            if let Ok(context) = json::parse(raw) {
+                if context["data"].is_object() {
+                    return Some(context["data"].clone());
+                }
+            }
+        }
+    }
+
+    // The older layout: window._feInjection = JSON.parse(decodeURIComponent("..."));
+    if let Some(raw) = between(page, "window._feInjection = JSON.parse(decodeURIComponent(\"", "\"))") {
+        if let Ok(injection) = json::parse(&percent_decode(raw)) {
+            if injection["currentData"].is_object() {
+                return Some(injection["currentData"].clone());
+            }
+        }
+    }
+
+    None
+}
+
+/// Extract the problem object from the JSON data embedded in a Luogu problem page.
+pub fn extract_problem_data(page: &str) -> Option<JsonValue> {
+    let data = extract_page_data(page)?;
+    if data["problem"].is_object() {
+        Some(data["problem"].clone())
+    } else {
+        None
+    }
+}
+
+/// Get the letter of the `index`-th problem of a contest: A, B, ..., Z, AA, AB, ...
+fn contest_letter(index: usize) -> String {
+    let mut index = index;
+    let mut letters = Vec::new();
+    loop {
+        letters.push((b'A' + (index % 26) as u8) as char);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Get the problems from the list in the page data.
+/// The problems of a contest are named by their letters, and the others by their problem ids.
+fn problems_in_list(url: &str, list: &JsonValue, lettered: bool) -> anyhow::Result<Vec<ProblemEntry>> {
+    let result = list
+        .members()
+        .filter_map(|item| item["problem"]["pid"].as_str())
+        .enumerate()
+        .map(|(i, pid)| ProblemEntry {
+            name: if lettered { contest_letter(i) } else { pid.to_string() },
+            problem: format!("luogu:{}", pid),
+        })
+        .collect::<Vec<ProblemEntry>>();
+    if result.is_empty() {
+        anyhow::bail!("Cannot find any problem in {url}. You may need to log in to view it. ");
+    }
+    Ok(result)
+}
+
+/// Get the problems of a training (i.e. a problem list).
+pub fn get_training_problems(training_id: &str) -> anyhow::Result<Vec<ProblemEntry>> {
+    let url = format!("https://www.luogu.com.cn/training/{}", training_id);
+    let data = extract_page_data(&get_remotely(&url)?).unwrap_or(JsonValue::Null);
+    problems_in_list(&url, &data["training"]["problems"], false)
+}
+
+/// Get the problems of a contest.
+pub fn get_contest_problems(contest_id: &str) -> anyhow::Result<Vec<ProblemEntry>> {
+    let url = format!("https://www.luogu.com.cn/contest/{}", contest_id);
+    let data = extract_page_data(&get_remotely(&url)?).unwrap_or(JsonValue::Null);
+    problems_in_list(&url, &data["contestProblems"], true)
+}
+
+/// Build the fetched problem from Luogu's problem object.
+fn problem_from_data(problem_id: &str, url: &str, data: &JsonValue) -> FetchedProblem {
+    // Luogu gives a limit for each test case, take the largest ones.
+    let time_limit = data["limits"]["time"].members().filter_map(|x| x.as_u32()).max().unwrap_or(1000);
+    let memory_limit_kb = data["limits"]["memory"].members().filter_map(|x| x.as_u32()).max().unwrap_or(256 * 1024);
+
+    let difficulty = data["difficulty"]
+        .as_usize()
+        .and_then(|d| DIFFICULTIES.get(d))
+        .map(|d| d.to_string());
+
+    let tag_ids = data["tags"].members().filter_map(|x| x.as_u32()).collect::<Vec<u32>>();
+
+    let mut samples = Vec::new();
+    for sample in data["samples"].members() {
+        samples.push((
+            sample[0].as_str().unwrap_or("").to_string(),
+            sample[1].as_str().unwrap_or("").to_string(),
+        ));
+    }
+
+    FetchedProblem {
+        oj: String::from("luogu"),
+        problem_id: problem_id.to_string(),
+        url: url.to_string(),
+        title: data["title"].as_str().unwrap_or(problem_id).to_string(),
+        time_limit,
+        memory_limit: memory_limit_kb.div_ceil(1024),
+        difficulty,
+        tags: get_tag_names(&tag_ids),
+        statement: statement_from_data(data, &samples),
+        samples,
+    }
+}
+
+/// Translate the tag ids into their names. If the tag list cannot be fetched, the ids will be used as names.
+fn get_tag_names(ids: &[u32]) -> Vec<String> {
+    if ids.is_empty() {
+        return vec![];
+    }
+    let tags = get_remotely("https://www.luogu.com.cn/_lfe/tags")
+        .ok()
+        .and_then(|content| json::parse(&content).ok());
+    ids.iter()
+        .map(|id| {
+            tags.as_ref()
+                .and_then(|tags| {
+                    tags["tags"]
+                        .members()
+                        .find(|tag| tag["id"].as_u32() == Some(*id))
+                        .and_then(|tag| tag["name"].as_str())
+                })
+                .map(String::from)
+                .unwrap_or_else(|| id.to_string())
+        })
+        .collect()
+}
+
+/// The headings before the input and output samples in different versions of Luogu's page.
+static SAMPLE_HEADINGS: [(&str, &str); 2] = [("输入样例", "输出样例"), ("输入 #", "输出 #")];
+
+/// Get the samples from the document of a Luogu problem page, i.e. the `<pre>` after `<h3>输入样例 #1</h3>`.
+/// It's an error if the numbers of inputs and outputs differ, since the samples couldn't be paired correctly.
+pub fn get_test_case_from_luogu_tree(dom: &Dom) -> anyhow::Result<Vec<(String, String)>> {
+    for (input_heading, output_heading) in SAMPLE_HEADINGS {
+        let inputs = select(&dom.children, &format!("h3:starts-with({input_heading}) + pre"));
+        let outputs = select(&dom.children, &format!("h3:starts-with({output_heading}) + pre"));
+        if !inputs.is_empty() {
+            return pair_samples(
+                inputs.iter().map(|input| text_content(&input.children)).collect(),
+                outputs.iter().map(|output| text_content(&output.children)).collect(),
+            );
+        }
+    }
+    Ok(vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = format!("{}/tests/fixtures/luogu/{}", env!("CARGO_MANIFEST_DIR"), name);
+        std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("Cannot read {path}: {err}"))
+    }
+
+    /// Get the samples in a fixture. The trailing whitespaces are removed, as the parser doesn't always keep them.
+    fn samples_of(name: &str) -> anyhow::Result<Vec<(String, String)>> {
+        let samples = get_test_case_from_luogu_tree(&Dom::parse(&fixture(name)).unwrap())?;
+        Ok(samples
+            .into_iter()
+            .map(|(input, output)| (input.trim_end().to_string(), output.trim_end().to_string()))
+            .collect())
+    }
+
+    fn pair(input: &str, output: &str) -> (String, String) {
+        (input.to_string(), output.to_string())
+    }
+
+    #[test]
+    fn samples_in_old_layout() {
+        assert_eq!(samples_of("old_layout.html").unwrap(), vec![pair("20 30", "50"), pair("1 2", "3")]);
+    }
+
+    #[test]
+    fn samples_in_new_layout() {
+        assert_eq!(samples_of("new_layout.html").unwrap(), vec![pair("20 30", "50"), pair("1 2", "3")]);
+    }
+
+    #[test]
+    fn samples_with_empty_code() {
+        assert_eq!(samples_of("empty_code.html").unwrap(), vec![pair("", "Hello, World!")]);
+    }
+
+    #[test]
+    fn samples_with_entities() {
+        assert_eq!(
+            samples_of("entities.html").unwrap(),
+            vec![pair("a < b && b > c\n\"x\" 'y'", "<> 中文")]
+        );
+    }
+
+    #[test]
+    fn samples_with_missing_output() {
+        assert!(samples_of("mismatched.html").is_err());
+    }
+
+    #[test]
+    fn no_samples() {
+        assert_eq!(samples_of("lentille_context.html").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn problem_data_in_lentille_context() {
+        let data = extract_problem_data(&fixture("lentille_context.html")).unwrap();
+        assert_eq!(data["pid"], "P1001");
+        assert_eq!(data["samples"].len(), 2);
+        assert_eq!(data["samples"][1][0], "20 30\n");
+        assert!(data["content"]["description"].as_str().unwrap().contains("$a, b$"));
+    }
+
+    #[test]
+    fn problem_data_in_fe_injection() {
+        let data = extract_problem_data(&fixture("fe_injection.html")).unwrap();
+        assert_eq!(data["pid"], "P1001");
+        assert_eq!(data["title"], "A+B Problem");
+        assert_eq!(data["samples"][0][1], "3\n");
+        assert_eq!(data["inputFormat"], "两个整数 $a, b$。");
+    }
+
+    #[test]
+    fn no_problem_data() {
+        assert!(extract_problem_data(&fixture("old_layout.html")).is_none());
+    }
+
+    #[test]
+    fn contest_letters() {
+        let letters = [0, 1, 25, 26, 27, 51, 52, 701, 702].map(contest_letter);
+        assert_eq!(letters, ["A", "B", "Z", "AA", "AB", "AZ", "BA", "ZZ", "AAA"]);
+    }
+
+    #[test]
+    fn statement_from_problem_data() {
+        let data = extract_problem_data(&fixture("lentille_context.html")).unwrap();
+        let statement = statement_from_data(&data, &[pair("1 2", "3")]);
+        let titles = statement.iter().map(|(title, _)| title.as_str()).collect::<Vec<&str>>();
+        assert_eq!(titles, vec!["题目描述", "输入格式", "输出格式", "输入输出样例", "说明/提示"]);
+    }
+}
@@ -4,39 +4,75 @@ use std::{
     fs::{self, File, OpenOptions},
     io::{stdin, Read, Write},
     path::{Path, PathBuf},
-    process::{Command, Stdio},
-    time::Duration,
+    process::{Child, Stdio},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
 };
 
 use crossterm::style::Stylize;
-use json::{object, JsonValue};
+use json::JsonValue;
 use wait_timeout::ChildExt;
 
 use crate::oi_helper::utils;
 
-use super::{resource, samples::Samples};
+use super::{
+    build::Build,
+    bundle,
+    checker,
+    compdb,
+    config::{self, Origin, WorkspaceConfig},
+    directives::Directives,
+    history::{SampleResult, Verdict},
+    languages::{self, Flags, Language},
+    pch,
+    problem::ProblemInfo,
+    resource,
+    samples::Samples,
+    template,
+    templates::{self, Registry},
+};
+
+/// If a problem with the global configuration has been warned about. It's loaded more than once for a command.
+static GLOBAL_CONFIG_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// How often the memory of a running test is measured.
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_millis(5);
 
 /// The workspace model.
 pub struct Workspace {
-    config: JsonValue,
+    config: WorkspaceConfig,
+    /// The content of `oi_ws.json`, which only contains the keys set in this workspace.
+    local: JsonValue,
+    /// The content of `global.json`.
+    global: JsonValue,
     global_config: Option<String>,
+    /// The path to `oi_ws.json`.
+    path: PathBuf,
 }
 
 #[allow(dead_code)]
 impl Workspace {
-    fn get_default_config(&self) -> JsonValue {
+    /// Load the global configuration from `global_cfg/global.json`, creating or updating it if neccessary.
+    /// This runs for every command, so problems with the file are only warned about, and the builtin defaults are used.
+    pub fn load_global_config(global_cfg: &Option<String>) -> JsonValue {
         // The default configuration, if the configuration doesn't exist.
-        let builtin_default = object! {
-            "initialzed": "true",
-            "oi_helper_version": crate::VERSION,
-            "cc_flags": "-std=c++11 -O2 -Wall -xc++ ",
-            "cc_template": "temp0",
-            "cc_default_extension": "cc",
-            "cc_compiler": "g++",
+        let builtin_default = WorkspaceConfig::default().to_json();
+
+        let warn = |message: String| {
+            if !GLOBAL_CONFIG_WARNED.swap(true, Ordering::Relaxed) {
+                eprintln!("{} {}", "[WARNING]".bold().yellow(), message);
+            }
+        };
+        // Write the configuration, or warn if it cannot be written.
+        let write = |path: &Path, cfg: &JsonValue| {
+            if let Err(err) = File::create(path).and_then(|mut f| f.write_all(cfg.dump().as_bytes())) {
+                warn(format!("Cannot write {}: {}. ", path.display(), err));
+            }
         };
 
         // If the configuration directory exists
-        if let Some(p) = &self.global_config {
+        if let Some(p) = global_cfg {
             // Construct a path to the global.json
             let pth = Path::new(&p);
             let mut pth_buf = pth.to_path_buf();
@@ -45,18 +81,28 @@ impl Workspace {
             // Check if the file exists
             if !pth_buf.as_path().exists() {
                 // If not, create a new file
-                let mut f = File::create(&pth_buf.as_path()).unwrap();
-                f.write_all(builtin_default.dump().as_bytes()).unwrap();
+                write(&pth_buf, &builtin_default);
                 builtin_default
             } else {
                 // Otherwise, read the file OR update the default file.
-                let mut f = File::open(&pth_buf.as_path()).unwrap();
-                let mut buffer = String::new();
-                f.read_to_string(&mut buffer).unwrap();
-                let ccfg = json::parse(&buffer).unwrap();
+                let buffer = match fs::read_to_string(&pth_buf) {
+                    Ok(buffer) => buffer,
+                    Err(err) => {
+                        warn(format!("Cannot read {}: {}. Using the builtin defaults. ", pth_buf.display(), err));
+                        return builtin_default;
+                    }
+                };
+                let ccfg = match json::parse(&buffer) {
+                    Ok(ccfg) => ccfg,
+                    Err(err) => {
+                        // Don't override the broken file, the user may want to fix it.
+                        warn(format!("Cannot parse {}: {}. Using the builtin defaults. ", pth_buf.display(), err));
+                        return builtin_default;
+                    }
+                };
 
                 // Check the version
-                if ccfg["oi_helper_version"].to_string() != crate::VERSION {
+                if ccfg["oi_helper_version"] != crate::VERSION {
                     // The global.json is from an older version
                     let mut mccfg = ccfg.clone();
 
@@ -70,8 +116,7 @@ impl Workspace {
                     // Update the version.
                     mccfg["oi_helper_version"] = JsonValue::String(String::from(crate::VERSION));
 
-                    let mut f = File::create(&pth_buf.as_path()).unwrap();
-                    f.write_all(mccfg.dump().as_bytes()).unwrap();
+                    write(&pth_buf, &mccfg);
                     mccfg
                 } else {
                     // If it's already the newest, just return what we read
@@ -83,50 +128,67 @@ impl Workspace {
         }
     }
 
-    /// Edit the global configuration file.
-    pub fn set_g_config(&mut self, key: &str, value: &str) -> Result<(), Option<String>> {
-        if let Some(p) = &self.global_config {
-            let pth = Path::new(p);
-            let mut pth_buf = pth.to_path_buf();
-            pth_buf.push("global.json");
-            let mut cfg = self.get_default_config();
-            cfg[key] = JsonValue::String(String::from(value));
-            let mut f = match File::create(&pth_buf.as_path()) {
-                Ok(f) => f,
-                Err(err) => {
-                    return Err(Some(format!(
-                        "Error saving global configuration file: {err}"
-                    )));
-                }
-            };
-            if let Err(err) = f.write_all(cfg.dump().as_bytes()) {
-                return Err(Some(format!(
-                    "Error saving global configuration file: {err}"
-                )));
+    /// Save `cfg` as the global configuration file.
+    fn save_global_config(global_cfg: &Option<String>, cfg: &JsonValue) -> Result<(), Option<String>> {
+        let p = match global_cfg {
+            Some(p) => p,
+            None => return Err(Some("Cannot edit the global configuration file.".to_string())),
+        };
+        let pth_buf = Path::new(p).join("global.json");
+        let mut f = match File::create(&pth_buf) {
+            Ok(f) => f,
+            Err(err) => {
+                return Err(Some(format!("Error saving global configuration file: {err}")));
             }
-            Ok(())
-        } else {
-            Err(Some(
-                "Cannot edit the global configuration file.".to_string(),
-            ))
+        };
+        if let Err(err) = f.write_all(cfg.dump().as_bytes()) {
+            return Err(Some(format!("Error saving global configuration file: {err}")));
         }
+        Ok(())
+    }
+
+    /// Edit the global configuration file.
+    pub fn set_g_config(global_cfg: &Option<String>, key: &str, value: &str, force: bool) -> Result<(), Option<String>> {
+        let mut cfg = Self::load_global_config(global_cfg);
+        config::set_in(&mut cfg, key, value, force)?;
+        Self::save_global_config(global_cfg, &cfg)
+    }
+
+    /// Remove a key from the global configuration file, so the builtin default is used.
+    pub fn unset_g_config(global_cfg: &Option<String>, key: &str) -> Result<(), Option<String>> {
+        let mut cfg = Self::load_global_config(global_cfg);
+        config::unset_in(&mut cfg, key)?;
+        Self::save_global_config(global_cfg, &cfg)
+    }
+
+    /// Get the typed global configuration and the origin of each value.
+    pub fn global_entries(global_cfg: &Option<String>) -> Result<Vec<(String, JsonValue, Origin)>, Option<String>> {
+        let global = Self::load_global_config(global_cfg);
+        let typed = WorkspaceConfig::from_json(&global, &JsonValue::Null).map_err(|errors| Some(errors.join("; ")))?;
+        Ok(typed
+            .keys()
+            .into_iter()
+            .map(|key| {
+                let origin = if global_cfg.is_some() && global.has_key(&key) { Origin::Global } else { Origin::Builtin };
+                let value = typed.get(&key).unwrap_or(JsonValue::Null);
+                (key, value, origin)
+            })
+            .collect())
     }
 
     /// Create from path.
-    pub fn create(path: &PathBuf, global_cfg: &Option<String>) -> Result<Self, Option<String>> {
-        let result = Self {
-            config: JsonValue::Null,
-            global_config: global_cfg.clone(),
+    /// Only the internal keys are written into `oi_ws.json`, the others come from the global configuration.
+    pub fn create(path: &Path, global_cfg: &Option<String>) -> Result<Self, Option<String>> {
+        let default_workspace_file = json::object! {
+            "oi_helper_version": crate::VERSION,
+            "initialzed": "true",
         };
-        let default_workspace_file = result.get_default_config();
-        let mut cfg_path = path.clone();
-
-        cfg_path.push("oi_ws.json");
+        let cfg_path = path.join("oi_ws.json");
         let cfg_file = cfg_path.as_path();
 
         // Check if the configuration exists.
         if !cfg_file.exists() {
-            let mut f = match File::create(&cfg_file) {
+            let mut f = match File::create(cfg_file) {
                 Ok(file) => file,
                 Err(err) => {
                     return Err(Some(format!("Cannot create workspace file: {}", err)));
@@ -145,8 +207,12 @@ impl Workspace {
             std::io::stdin().read_line(&mut choice).unwrap();
             if choice.trim().to_uppercase() == "Y" {
                 // User chose yes, then override it.
-                let mut f =
-                    File::create(&cfg_file).expect("cannot create the workspace file. stopped.");
+                let mut f = match File::create(cfg_file) {
+                    Ok(file) => file,
+                    Err(err) => {
+                        return Err(Some(format!("Cannot create workspace file: {}", err)));
+                    }
+                };
                 match f.write_all(default_workspace_file.dump().as_bytes()) {
                     Ok(_) => {}
                     Err(err) => {
@@ -157,14 +223,29 @@ impl Workspace {
                 return Err(None);
             }
         }
-        Ok(Self::from_json(default_workspace_file, global_cfg))
+        Self::from_json(&default_workspace_file, &cfg_path, global_cfg)
     }
 
-    /// Initialize from json.
-    pub fn from_json(json: JsonValue, global_cfg: &Option<String>) -> Self {
-        Self {
-            config: json.clone(),
-            global_config: global_cfg.clone(),
+    /// Initialize from json, which is the content of the configuration file `path`.
+    /// The keys missing in `json` are taken from the global configuration.
+    pub fn from_json(json: &JsonValue, path: &Path, global_cfg: &Option<String>) -> Result<Self, Option<String>> {
+        let global = Self::load_global_config(global_cfg);
+        match WorkspaceConfig::from_json(&config::without_global_only(json), &global) {
+            Ok(config) => Ok(Self {
+                config,
+                local: json.clone(),
+                global,
+                global_config: global_cfg.clone(),
+                path: path.to_path_buf(),
+            }),
+            Err(errors) => {
+                eprintln!("{}", "The workspace configuration is invalid: ".red());
+                for err in errors {
+                    eprintln!("  - {}", err);
+                }
+                eprintln!("{}", "[HINT] Use `oi_helper config` to set the values with the right types, or fix the oi_ws.json by hand. ".bold().yellow());
+                Err(None)
+            }
         }
     }
 
@@ -182,7 +263,7 @@ impl Workspace {
                     "{}{}{}",
                     "[HINT] Have you executed ".yellow().bold(),
                     "oi_helper init".cyan().bold(),
-                    " or are you in the root directory of the workspace? "
+                    " or are you inside a workspace? "
                         .bold()
                         .yellow()
                 );
@@ -203,256 +284,543 @@ impl Workspace {
                 return Err(None);
             }
         }
-        return Ok(Self::from_json(
-            json::parse(&file_content).expect("the oi_ws.json is not a valid json file. stopped."),
-            global_cfg,
-        ));
+        let json = match json::parse(&file_content) {
+            Ok(json) => json,
+            Err(err) => {
+                return Err(Some(format!("{} is not a valid JSON file: {}", path.display(), err)));
+            }
+        };
+        if !json.has_key("oi_helper_version") {
+            // Cannot get the version, which means it's broken.
+            return Err(Some(format!(
+                "The workspace config {} is broken or not in the correct format: missing `oi_helper_version`. Stopped.",
+                path.display()
+            )));
+        }
+        Self::from_json(&json, path, global_cfg)
+    }
+
+    /// Find `oi_ws.json` in `start` or its parent directories.
+    pub fn locate(start: &Path) -> Option<PathBuf> {
+        // Relative paths like `.` don't have parents, so make it absolute first.
+        let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+        start
+            .ancestors()
+            .map(|dir| dir.join("oi_ws.json"))
+            .find(|path| path.is_file())
+    }
+
+    /// Open the workspace containing the current directory, or the one given with `--workspace`.
+    pub fn open(workspace: Option<&Path>, global_cfg: &Option<String>) -> Result<Self, Option<String>> {
+        let path = match workspace {
+            Some(path) if path.is_dir() => path.join("oi_ws.json"),
+            Some(path) => path.to_path_buf(),
+            None => {
+                let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+                // If nothing is found, let `from_file` report the missing file.
+                Self::locate(&cwd).unwrap_or_else(|| cwd.join("oi_ws.json"))
+            }
+        };
+        if crate::is_debug() {
+            println!("[DEBUG] Using workspace {}", path.display());
+        }
+        Self::from_file(&path, global_cfg)
+    }
+
+    /// Get the root directory of the workspace.
+    pub fn root(&self) -> &Path {
+        self.path.parent().unwrap_or_else(|| Path::new("."))
     }
 
     /// Check the version of the workspace.
-    pub fn check_version(&mut self, p: &str) -> Result<(), Option<String>> {
-        // If have this key.
-        if self.config.has_key("oi_helper_version") {
-            // Get the version string.
-            let version = self.config["oi_helper_version"].clone();
-
-            // Check the version.
-            if version.to_string() != crate::VERSION {
-                eprintln!("{} The version of oi_helper is {} but the workspace version is {}. Load it anyway? [Y/{}]", "[WARNING]".bold().yellow(), crate::VERSION.bold().green(), version.to_string().bold().red(), "N".bold().blue());
-                eprintln!("{}", "[HINT] You can use `oi_helper update` to update your workspace to the newest version safely.".bold().yellow());
-                let mut u_c = String::new();
-                stdin().read_line(&mut u_c).unwrap();
-
-                // Unsafely update the workspace.
-                if u_c.trim().to_uppercase() == "Y" {
-                    self.config["oi_helper_version"] =
-                        JsonValue::String(String::from(crate::VERSION));
-                    self.config["__unsafe_updating"] = JsonValue::Boolean(true);
-                    self.save_config(Path::new(p))?;
-                } else {
-                    // Just exit the program if the user didn't want to load the workspace.
-                    // Maybe they'll update the workspace in a safe way later.
-                    return Err(None);
-                }
+    pub fn check_version(&mut self) -> Result<(), Option<String>> {
+        // Get the version string.
+        let version = self.config.oi_helper_version.clone();
+
+        // Check the version.
+        if version != crate::VERSION {
+            eprintln!("{} The version of oi_helper is {} but the workspace version is {}. Load it anyway? [Y/{}]", "[WARNING]".bold().yellow(), crate::VERSION.bold().green(), version.bold().red(), "N".bold().blue());
+            eprintln!("{}", "[HINT] You can use `oi_helper update` to update your workspace to the newest version safely.".bold().yellow());
+            let mut u_c = String::new();
+            stdin().read_line(&mut u_c).unwrap();
+
+            // Unsafely update the workspace.
+            if u_c.trim().to_uppercase() == "Y" {
+                self.put("oi_helper_version", JsonValue::from(crate::VERSION));
+                self.put("__unsafe_updating", JsonValue::from(true));
+                self.save_config()?;
             } else {
-                // Check if the workspace is unsafe.
-                if self.config.has_key("__unsafe_updating") {
-                    if let Some(uu) = self.config["__unsafe_updating"].as_bool() {
-                        // It's true!
-                        if uu {
-                            eprintln!(
-                                "{}",
-                                "[WARNING] Running in an unsafe updated workspace. "
-                                    .bold()
-                                    .yellow()
-                            );
-                            eprintln!(
-                                "{}{}{}",
-                                "[HINT] Use ".bold().yellow(),
-                                "oi_helper update".bold().cyan(),
-                                " to update the workspace safely. ".bold().yellow()
-                            );
-                        }
-                    }
-                }
-                // Otherwise, it's must be safe and the newest
+                // Just exit the program if the user didn't want to load the workspace.
+                // Maybe they'll update the workspace in a safe way later.
+                return Err(None);
             }
-        } else {
-            // Cannot get the version, which means it's broken.
-            return Err(Some(String::from(
-                "The workspace config is broken or not in the correct format. Stopped.",
-            )));
+        } else if self.config.unsafe_updating {
+            // Check if the workspace is unsafe.
+            eprintln!(
+                "{}",
+                "[WARNING] Running in an unsafe updated workspace. "
+                    .bold()
+                    .yellow()
+            );
+            eprintln!(
+                "{}{}{}",
+                "[HINT] Use ".bold().yellow(),
+                "oi_helper update".bold().cyan(),
+                " to update the workspace safely. ".bold().yellow()
+            );
         }
+        // Otherwise, it's must be safe and the newest
         Ok(())
     }
 
-    /// Set the configuration.
-    pub fn set_config(&mut self, key: &str, value: &str) {
-        self.config[key] = JsonValue::String(String::from(value));
+    /// Get the typed configuration.
+    pub fn config(&self) -> &WorkspaceConfig {
+        &self.config
+    }
+
+    /// Set a checked value in both `oi_ws.json` and the typed configuration.
+    fn put(&mut self, key: &str, value: JsonValue) {
+        self.config.assign(key, &value);
+        self.local[key] = value;
+    }
+
+    /// Set the configuration. Unknown keys are rejected unless `force` is true.
+    /// The global-only keys are rejected, since they are read before the workspace is opened.
+    pub fn set_config(&mut self, key: &str, value: &str, force: bool) -> Result<(), Option<String>> {
+        if config::is_global_only(key) {
+            return Err(Some(format!("`{key}` can only be set in the global configuration. Use `oi_helper global-config set {key} ...` instead. ")));
+        }
+        config::set_in(&mut self.local, key, value, force)?;
+        self.reload()
+    }
+
+    /// Rebuild the typed configuration after `oi_ws.json` is changed.
+    fn reload(&mut self) -> Result<(), Option<String>> {
+        self.config = WorkspaceConfig::from_json(&config::without_global_only(&self.local), &self.global).map_err(|errors| Some(errors.join("; ")))?;
+        Ok(())
+    }
+
+    /// Remove a key from `oi_ws.json`, so the value in the global configuration is used.
+    pub fn unset_config(&mut self, key: &str) -> Result<(), Option<String>> {
+        config::unset_in(&mut self.local, key)?;
+        self.reload()
+    }
+
+    /// Get where the value of a key comes from.
+    pub fn origin(&self, key: &str) -> Origin {
+        if !config::is_global_only(key) && config::get_in(&self.local, key).is_some() {
+            Origin::Workspace
+        } else if self.global_config.is_some() && config::get_in(&self.global, key).is_some() {
+            Origin::Global
+        } else {
+            Origin::Builtin
+        }
+    }
+
+    /// Get all the values and their origins.
+    pub fn entries(&self) -> Vec<(String, JsonValue, Origin)> {
+        self.config
+            .keys()
+            .into_iter()
+            .map(|key| {
+                let value = self.config.get(&key).unwrap_or(JsonValue::Null);
+                let origin = self.origin(&key);
+                (key, value, origin)
+            })
+            .collect()
     }
 
     /// Save the configuration.
-    pub fn save_config(&self, path: &Path) -> Result<(), Option<String>> {
-        let mut file = match OpenOptions::new().write(true).truncate(true).open(path) {
+    pub fn save_config(&self) -> Result<(), Option<String>> {
+        let mut file = match OpenOptions::new().write(true).truncate(true).open(&self.path) {
             Ok(f) => f,
             Err(err) => {
                 return Err(Some(format!("Cannot save configuration file: {err}")));
             }
         };
-        file.write_all(self.config.dump().as_bytes()).unwrap();
+        if let Err(err) = file.write_all(self.local.dump().as_bytes()) {
+            return Err(Some(format!("Cannot write to {}: {}", self.path.display(), err)));
+        }
         Ok(())
     }
 
-    /// Create a new C++ source file.
-    pub fn create_cpp(
+    /// Get the file name of a source. If `name` doesn't have the extension of a supported language, `cc_default_extension` is added.
+    pub fn source_name(&self, name: &str) -> String {
+        if languages::by_path(Path::new(name)).is_some() {
+            String::from(name)
+        } else {
+            String::from(name) + "." + self.config.cc_default_extension.as_str()
+        }
+    }
+
+    /// Find the language of a source file.
+    fn language_of(&self, source: &Path) -> Result<&'static Language, Option<String>> {
+        match languages::by_path(source) {
+            Some(language) => Ok(language),
+            None => Err(Some(format!(
+                "Cannot tell the language of {}. Supported extensions: {}",
+                source.display(),
+                languages::extensions().join(", ")
+            ))),
+        }
+    }
+
+    /// Check if the source file of `name` already exists, in any language.
+    pub fn source_exists(&self, name: &str) -> bool {
+        Path::new(&self.source_name(name)).exists()
+            || languages::extensions()
+                .iter()
+                .any(|ext| Path::new(&format!("{name}.{ext}")).exists())
+    }
+
+    /// Create a new source file. C++ sources use the template given, and other languages use their own templates.
+    /// `vars` are the extra variables of the template, which override the built-in ones.
+    pub fn create_source(
         &self,
         name: &str,
         template: &str,
         maxn: &str,
         maxl: &str,
         debug_kit: bool,
+        vars: &[(String, String)],
     ) -> Result<(), Option<String>> {
-        let real_name = if name.ends_with(".cpp") && name.ends_with(".cc") && name.ends_with(".cxx")
-        {
-            String::from(name)
+        let real_name = self.source_name(name);
+        let language = self.language_of(Path::new(&real_name))?;
+        let mut buffer = String::new();
+        let mut template_dir = PathBuf::from(".");
+        let template_scheme = self.config.cc_template.as_str();
+
+        // Choose the tempalte. The named templates come first, so they can override the built-in ones.
+        let template = if let Some((_, path)) = self.templates().find(template) {
+            buffer = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(err) => return Err(Some(format!("Failed to read template file {} due to error: {}", path.display(), err))),
+            };
+            template_dir = path.parent().unwrap_or(&template_dir).to_path_buf();
+            buffer.as_str()
+        } else if let Some(builtin) = templates::builtin(template, template_scheme) {
+            // The built-in templates are for C++, other languages use their own templates instead.
+            if language.is_cpp() {
+                builtin
+            } else {
+                language.template
+            }
         } else {
-            String::from(name) + "." + self.config["cc_default_extension"].to_string().as_str()
+            // Try to treat the template name as a path to the template file.
+            if let Ok(mut f) = File::open(Path::new(template)) {
+                // Try to read it.
+                match f.read_to_string(&mut buffer) {
+                    Ok(_) => {}
+                    Err(err) => {
+                        return Err(Some(format!(
+                            "Failed to read template file {} due to error: {}",
+                            template, err
+                        )))
+                    }
+                }
+                // Includes in the template file are relative to it.
+                if let Some(dir) = Path::new(template).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                    template_dir = dir.to_path_buf();
+                }
+                buffer.as_str()
+            } else {
+                // All tryings were failed. Tell the user about that.
+                eprintln!("Invalid template: {}", template);
+                eprintln!(
+                    "{}",
+                    "Usable tempaltes: dp, default, dp-2d, empty, the ones in `oi_helper template list`, [path/to/template]"
+                        .bold()
+                        .yellow()
+                );
+                return Err(None);
+            }
         };
+
+        // Fill in all the placeholders.
+        let mut context = template::Context::new(&template_dir);
+        context.set("", name);
+        context.set("name", name);
+        context.set("class", &Path::new(&real_name).file_stem().unwrap_or_default().to_string_lossy());
+        context.set("maxn_value", maxn);
+        context.set("maxl_value", maxl);
+        context.set("debug_kit", if debug_kit { resource::CPP_TEMPLATE_DEBUG_KIT } else { "" });
+        context.set("date", &utils::datetime::today());
+        context.set("author", &self.config.author);
+        context.set("language", language.name);
+        let group = Path::new(&real_name).with_extension("");
+        let info = ProblemInfo::of_group(&group.to_string_lossy())?;
+        for key in ["url", "title"] {
+            context.set(key, &info.get_str(key));
+        }
+        context.set("file_io", &Samples::group_file_io(&group.to_string_lossy()).unwrap_or_default());
+        for (key, value) in vars {
+            context.set(key, value);
+        }
+        let content = match context.render(template) {
+            Ok(content) => content,
+            Err(err) => return Err(Some(format!("Cannot render the template: {err}"))),
+        };
+
         let mut file = match File::create(Path::new(&real_name)) {
             Ok(file) => file,
             Err(_) => {
                 return Err(Some(format!(
-                    "Failed to create the C++ source file. Please check your configuration."
+                    "Failed to create the {} source file. Please check your configuration.",
+                    language.name
                 )))
             }
         };
-        let template_scheme_obj = self.config["cc_template"].to_string();
-        let mut buffer = String::new();
-        let template_scheme = template_scheme_obj.as_str();
-
-        // Choose the tempalte.
-        let template = match template {
-            "dp" => match template_scheme {
-                "temp1" => resource::CPP_DP_TEMPLATE_0.trim_start(),
-                "temp0" | _ => resource::CPP_DP_TEMPLATE_1.trim_end(),
-            },
-            "default" => match template_scheme {
-                "temp1" => resource::CPP_TEMPLATE_1.trim_start(),
-                "temp0" | _ => resource::CPP_TEMPLATE_0.trim_start(),
-            },
-            "dp-2d" => match template_scheme {
-                "temp1" => resource::CPP_DP_2D_TEMPLATE_1.trim_start(),
-                "temp0" | _ => resource::CPP_DP_2D_TEMPLATE_0.trim_start(),
-            },
-            "empty" => "",
-            _ => {
-                // Try to treat the template name as a path to the template file.
-                if let Ok(mut f) = File::open(&Path::new(template)) {
-                    // Try to read it.
-                    match f.read_to_string(&mut buffer) {
-                        Ok(_) => {}
-                        Err(err) => {
-                            return Err(Some(format!(
-                                "Failed to read template file {} due to error: {}",
-                                template, err
-                            )))
-                        }
-                    }
-                    buffer.as_str()
-                } else {
-                    // All tryings were failed. Tell the user about that.
-                    eprintln!("Invalid template: {}", template);
-                    eprintln!(
-                        "{}",
-                        "Usable tempaltes: dp, default, dp-2d, empty, [path/to/template]"
-                            .bold()
-                            .yellow()
-                    );
-                    return Err(None);
-                }
+        if let Err(err) = file.write_all(content.as_bytes()) {
+            return Err(Some(format!("Cannot write template due to error: {err}")));
+        }
+
+        self.update_compdb_on_create();
+        Ok(())
+    }
+
+    /// Get the named templates of the workspace and the user.
+    pub fn templates(&self) -> Registry {
+        Registry::new(&self.global_config, Some(self.root()), "templates")
+    }
+
+    /// Keep the compilation database up to date if `compdb_on_create` is set.
+    /// The source has been created anyway, so only warn if it fails.
+    fn update_compdb_on_create(&self) {
+        if self.config.compdb_on_create {
+            if let Err(err) = self.write_compdb(None) {
+                eprintln!("{} Cannot update compile_commands.json: {}", "[WARNING]".bold().yellow(), err.unwrap_or_default());
             }
+        }
+    }
+
+    /// Get the build directory of the workspace.
+    pub fn target_dir(&self) -> PathBuf {
+        self.root().join("target")
+    }
+
+    /// Get the path of the build output of `source` with `profile`, without extension.
+    /// The directories of the source inside the workspace are kept, so sources with the same name don't conflict.
+    fn output_path(&self, source: &Path, profile: &str) -> PathBuf {
+        let source = fs::canonicalize(source).unwrap_or_else(|_| source.to_path_buf());
+        let root = fs::canonicalize(self.root()).unwrap_or_else(|_| self.root().to_path_buf());
+        let relative = match source.strip_prefix(&root) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => PathBuf::from(source.file_name().unwrap_or_default()),
         };
+        self.target_dir().join(profile).join(relative.with_extension(""))
+    }
 
-        // Fill in all the placeholders and write.
-        if let Err(err) = file.write_all(
-            template
-                .replace("{##}", name)
-                .replace("{#maxn_value#}", maxn)
-                .replace("{#debug_kit#}", {
-                    if debug_kit {
-                        resource::CPP_TEMPLATE_DEBUG_KIT
-                    } else {
-                        ""
-                    }
-                })
-                .replace("{#maxl_value#}", maxl)
-                .as_bytes(),
-        ) {
-            return Err(Some(format!("Cannot write template due to error: {err}")));
+    /// Get the flags of a build profile.
+    fn profile_flags(&self, profile: &str) -> Result<Vec<String>, Option<String>> {
+        let profile_flags = self.config.profile(profile)?;
+        if crate::is_debug() {
+            println!("[DEBUG] Using build profile {}: {}", profile, profile_flags);
         }
+        Ok(Self::parse_args(profile_flags))
+    }
+
+    /// Get the compiler flags of a build profile, which are `cc_flags` followed by the profile's flags.
+    fn profile_args(&self, profile: &str) -> Result<Vec<String>, Option<String>> {
+        let mut args = Self::parse_args(&self.config.cc_flags);
+        args.extend(self.profile_flags(profile)?);
+        Ok(args)
+    }
+
+    /// Get the compiler flags of a source, and the directives in it.
+    fn compile_args(
+        &self,
+        source: &Path,
+        language: &Language,
+        use_debug: bool,
+        profile: &str,
+    ) -> Result<(Vec<String>, Directives), Option<String>> {
+        let directives = Directives::from_file(source, language.comment)?;
+        let mut args = match language.flags {
+            Flags::Workspace => self.profile_args(profile)?,
+            Flags::Profile => self.profile_flags(profile)?,
+            Flags::Directives => vec![],
+        };
+        if language.flags == Flags::Directives {
+            args.extend(directives.flags.iter().cloned());
+        } else {
+            if use_debug {
+                args.push(String::from("-D__DEBUG__"));
+            }
+            args.extend(directives.compile_flags());
+        }
+        Ok((args, directives))
+    }
+
+    /// Get the values of the placeholders in the commands of a language.
+    fn command_vars(&self, source: &Path, output: &Path) -> Vec<(&'static str, String)> {
+        vec![
+            ("source", source.to_string_lossy().to_string()),
+            ("output", output.to_string_lossy().to_string()),
+            ("exe", String::from(std::env::consts::EXE_SUFFIX)),
+            ("class", source.file_stem().unwrap_or_default().to_string_lossy().to_string()),
+            ("cc_compiler", self.config.cc_compiler.clone()),
+        ]
+    }
+
+    /// Write `compile_commands.json` for all the C and C++ sources in the workspace. Returns the number of sources.
+    pub fn write_compdb(&self, profile: Option<&str>) -> Result<usize, Option<String>> {
+        let profile = profile.unwrap_or(&self.config.default_profile);
+        let root = fs::canonicalize(self.root()).unwrap_or_else(|_| self.root().to_path_buf());
+        let extensions = languages::LANGUAGES
+            .iter()
+            .filter(|lang| lang.flags != Flags::Directives)
+            .flat_map(|lang| lang.extensions.iter().copied())
+            .collect::<Vec<&str>>();
+        let mut entries = Vec::new();
+        for source in compdb::find_sources(&root, &extensions) {
+            let language = self.language_of(&source)?;
+            // The precompiled header isn't included, since clang cannot read the ones built by GCC.
+            let args = match self.compile_args(&source, language, false, profile) {
+                Ok((args, _)) => args,
+                Err(err) => {
+                    // Don't let one broken source break the whole database.
+                    eprintln!("{} {}", "[WARNING]".bold().yellow(), err.unwrap_or_default());
+                    self.profile_args(profile)?
+                }
+            };
+            let vars = self.command_vars(&source, &self.output_path(&source, profile));
+            entries.push(compdb::entry(&languages::expand(language.compile, &vars, &args), &source));
+        }
+        let count = entries.len();
+        compdb::write(&root, entries)?;
+        Ok(count)
+    }
+
+    /// Precompile `bits/stdc++.h` for a build profile.
+    pub fn build_pch(&self, profile: Option<&str>) -> Result<(), Option<String>> {
+        let profile = profile.unwrap_or(&self.config.default_profile);
+        let args = self.profile_args(profile)?;
+        pch::build(&self.target_dir(), &self.config.cc_compiler, &args)?;
         Ok(())
     }
 
-    fn compile_cpp(
+    /// Compile the source into the build directory unless it's up to date. Interpreted languages are not compiled.
+    /// Returns the command running the program, the directives in the source and the language.
+    fn compile(
         &self,
         real_name: &str,
-        executable_name: &str,
         use_debug: bool,
-    ) -> Result<(), Option<String>> {
-        match Command::new(self.config["cc_compiler"].to_string().as_str())
-            .args(self.parse_args())
-            .arg(format!("-o"))
-            .arg(format!("{}", executable_name))
-            .arg({
-                if use_debug {
-                    "-D__DEBUG__"
-                } else {
-                    ""
+        profile: Option<&str>,
+    ) -> Result<(Vec<String>, Directives, &'static Language), Option<String>> {
+        // Use the absolute path, so the program can be run in other directories.
+        let source = &fs::canonicalize(real_name).unwrap_or_else(|_| PathBuf::from(real_name));
+        let language = self.language_of(source)?;
+        let profile = profile.unwrap_or(&self.config.default_profile);
+        let (mut args, directives) = self.compile_args(source, language, use_debug, profile)?;
+
+        // Use the precompiled header built with the same flags.
+        if language.is_cpp() {
+            if let Some(dir) = pch::find(&self.target_dir(), &self.config.cc_compiler, &args) {
+                if crate::is_debug() {
+                    println!("[DEBUG] Using precompiled header in {}", dir.display());
                 }
-            })
-            .arg("--")
-            .arg(real_name)
-            .status()
-        {
-            Ok(_) => {
-                println!("{}", "Compiled. ".bold().green());
-                Ok(())
+                args.push(String::from("-I"));
+                args.push(dir.to_string_lossy().to_string());
             }
-            Err(_) => Err(Some(format!(
-                "Failed to compile the program. Stopped. (CE(0))"
-            ))),
         }
+
+        let vars = self.command_vars(source, &self.output_path(source, profile));
+        if language.is_compiled() {
+            Build {
+                command: languages::expand(language.compile, &vars, &args),
+                source: source.to_path_buf(),
+                output: PathBuf::from(languages::expand(&[language.output_file], &vars, &[]).remove(0)),
+            }
+            .run()?;
+        }
+        Ok((languages::expand(language.run, &vars, &[]), directives, language))
     }
 
-    /// Run a C++ source file.
-    pub fn run_cpp(&self, name: &str, use_debug: bool) -> Result<(), Option<String>> {
-        // Get the real name.
-        let real_name = if name.ends_with(".cpp") && name.ends_with(".cc") && name.ends_with(".cxx")
-        {
-            String::from(name)
-        } else {
-            String::from(name) + "." + self.config["cc_default_extension"].to_string().as_str()
+    /// Merge a C or C++ source and its local includes into one file for submitting, and compile it if `verify` is set.
+    /// Returns the path to the bundle.
+    pub fn bundle(&self, name: &str, verify: bool, profile: Option<&str>) -> Result<PathBuf, Option<String>> {
+        let source = PathBuf::from(self.source_name(name));
+        if self.language_of(&source)?.flags == Flags::Directives {
+            return Err(Some(format!("Only C and C++ sources can be bundled, but {} isn't one. ", source.display())));
+        }
+        let content = bundle::bundle(&source)?;
+        let output = bundle::output_path(&source);
+        if let Err(err) = fs::write(&output, content) {
+            return Err(Some(format!("Cannot write {}: {}", output.display(), err)));
+        }
+        if verify {
+            self.compile(&output.to_string_lossy(), false, profile)?;
+        }
+        Ok(output)
+    }
+
+    /// Check a C++ source for the mistakes which may get it disqualified in official contests,
+    /// and if it compiles with the official flags. Fails if anything serious is found.
+    pub fn check(&self, name: &str, options: &checker::Options) -> Result<(), Option<String>> {
+        let source = PathBuf::from(self.source_name(name));
+        let language = self.language_of(&source)?;
+        if !language.is_cpp() {
+            return Err(Some(format!("Only C++ sources can be checked, but {} isn't one. ", source.display())));
+        }
+        let content = match fs::read_to_string(&source) {
+            Ok(content) => content,
+            Err(err) => return Err(Some(format!("Cannot read {}: {}", source.display(), err))),
         };
+        match &options.file_io {
+            Some(name) => println!("Checking {} (file IO: {}.in, {}.out)... ", source.display(), name, name),
+            None => println!("Checking {} (standard IO)... ", source.display()),
+        }
 
-        // Generate the executable's name.
-        let executable_name = real_name.split('.').collect::<Vec<&str>>()[0];
+        let findings = checker::scan(&content, options);
+        for finding in &findings {
+            println!("{}", finding);
+        }
 
-        // Check if the old build target is already built and haven't been removed yet.
-        if Path::new(executable_name).exists() {
-            match fs::remove_file(Path::new(executable_name)) {
-                Ok(_) => {}
-                Err(err) => {
-                    return Err(Some(format!(
-                        "failed to clean the old built target: {}",
-                        err
-                    )));
-                }
-            }
+        // Compile with the official flags only, since the judges don't know the workspace's flags.
+        let flags = checker::OFFICIAL_FLAGS.iter().map(|flag| flag.to_string()).collect::<Vec<String>>();
+        let vars = self.command_vars(&source, &self.output_path(&source, "official"));
+        let compiled = Build {
+            command: languages::expand(language.compile, &vars, &flags),
+            source: source.clone(),
+            output: PathBuf::from(languages::expand(&[language.output_file], &vars, &[]).remove(0)),
+        }
+        .run();
+        match &compiled {
+            Ok(_) => println!("{} Compiles with {}.", "[OK]".bold().green(), flags.join(" ")),
+            Err(_) => println!("{} Doesn't compile with {}.", "[ERROR]".bold().red(), flags.join(" ")),
         }
 
+        let errors = findings.iter().filter(|f| f.severity == checker::Severity::Error).count() + compiled.is_err() as usize;
+        let warnings = findings.len() + compiled.is_err() as usize - errors;
+        let summary = format!("{} error(s), {} warning(s). ", errors, warnings);
+        if errors > 0 {
+            println!("{}", summary.bold().red());
+            return Err(None);
+        }
+        println!("{}", summary.bold().green());
+        Ok(())
+    }
+
+    /// Run a source file.
+    pub fn run_source(&self, name: &str, use_debug: bool, profile: Option<&str>) -> Result<(), Option<String>> {
         // Compile the target.
-        self.compile_cpp(&real_name, executable_name, use_debug)?;
+        let (run, directives, _) = self.compile(&self.source_name(name), use_debug, profile)?;
 
         // Run the target.
-        match Command::new(format!("./{}", executable_name)).status() {
+        match directives.command(&run).status() {
             Ok(_) => {}
             Err(_) => {
                 return Err(Some("Runtime error ocurred. ".to_string()));
             }
         }
 
-        // Finally remove the file.
-        if let Err(err) = fs::remove_file(Path::new(&format!("./{}", executable_name))) {
-            return Err(Some(format!("Failed to clean the compiled target: {err}")));
-        }
-
         Ok(())
     }
 
-    fn parse_args(&self) -> Vec<String> {
+    /// Split the flags into arguments. Double quotes can be used for arguments containing spaces.
+    fn parse_args(source: &str) -> Vec<String> {
         let mut result = Vec::<String>::new();
         let mut buffer = String::new();
-        let source = self.config["cc_flags"].to_string();
         let mut status = 0;
 
         for i in source.chars() {
@@ -466,7 +834,7 @@ impl Workspace {
                 }
 
                 1 => {
-                    if buffer != "" {
+                    if !buffer.is_empty() {
                         result.push(buffer);
                         buffer = String::new();
                         if i != ' ' {
@@ -493,158 +861,157 @@ impl Workspace {
                 _ => {}
             }
         }
+        // The last argument isn't followed by a space.
+        if !buffer.is_empty() {
+            result.push(buffer);
+        }
         result
     }
 
     /// Display the info of a workspace.
     pub fn display_info(&self) {
-        if self.config.has_key("oi_helper_version") {
-            println!(
-                "Current Workspace's OI Helper Version (oi_helper_version): {} {}",
-                self.config["oi_helper_version"].to_string(),
-                if self.config.has_key("__unsafe_updating") {
-                    if let Some(uu) = self.config["__unsafe_updating"].as_bool() {
-                        if uu {
-                            "UNSAFE UPDATED".yellow().bold()
-                        } else {
-                            "".stylize()
-                        }
-                    } else {
-                        "MAYBE BROKEN".red().bold()
-                    }
-                } else {
-                    "".reset()
-                }
-            );
-        }
-        if self.config.has_key("cc_flags") {
-            println!(
-                "Current C++ Compiler Flags (cc_flags): {}",
-                self.config["cc_flags"].to_string()
-            );
-        }
-        if self.config.has_key("cc_template") {
-            println!(
-                "Current Template Theme (cc_template): {}",
-                self.config["cc_template"].to_string()
-            );
-        }
-        if self.config.has_key("cc_default_extension") {
-            println!(
-                "Current C++ Extension (cc_default_extension): {}",
-                self.config["cc_default_extension"].to_string()
-            );
-        }
-        if self.config.has_key("cc_compiler") {
+        println!(
+            "Current Workspace's OI Helper Version (oi_helper_version): {} {}",
+            self.config.oi_helper_version,
+            if self.config.unsafe_updating {
+                "UNSAFE UPDATED".yellow().bold()
+            } else {
+                "".stylize()
+            }
+        );
+        let profile = &self.config.default_profile;
+        let pch_status = if !pch::supported(&self.config.cc_compiler) {
+            "unsupported, only GCC is supported".yellow()
+        } else {
+            match self.profile_args(profile).ok().and_then(|args| pch::find(&self.target_dir(), &self.config.cc_compiler, &args)) {
+                Some(_) => "valid".green(),
+                None => "not built, use `oi_helper pch build`".yellow(),
+            }
+        };
+        println!("Precompiled bits/stdc++.h for the {} profile: {}", profile, pch_status);
+        for (key, value, origin) in self.entries() {
+            let description = config::find_key(&key).map(|k| k.description).unwrap_or("Unknown key");
+            if config::find_key(&key).map(|k| k.internal).unwrap_or(false) {
+                continue;
+            }
             println!(
-                "Current C++ Compiler (cc_compiler): {}",
-                self.config["cc_compiler"].to_string()
+                "{} ({}): {} {}",
+                description.trim_end_matches('.'),
+                key,
+                value,
+                format!("[{}]", origin).dark_grey()
             );
         }
     }
 
     /// Update the workspace file to the newest version.
+    /// The keys missing in an older version's workspace file come from the global configuration, so they aren't copied.
+    /// The global-only keys copied into the workspace file by older versions are removed.
     pub fn update(&mut self) {
-        if self.config.has_key("oi_helper_version") {
-            self.config["oi_helper_version"] = JsonValue::String(String::from(crate::VERSION));
-        }
-        let default = &self.get_default_config();
-        for i in default.entries().map(|x| x.0) {
-            if !self.config.has_key(i) {
-                self.config[i] = default[i].clone();
+        self.local = config::without_global_only(&self.local);
+        self.put("oi_helper_version", JsonValue::from(crate::VERSION));
+        self.put("__unsafe_updating", JsonValue::from(false));
+    }
+
+    /// Get the directory where the tests of a source using file IO are run, i.e. `target/.run/<name>/`.
+    /// The name starts with a dot, so it doesn't collide with the build directory of a profile.
+    fn run_dir(&self, source: &Path) -> PathBuf {
+        self.output_path(source, ".run")
+    }
+
+    /// Empty the directory where a test is run, and put the input file into it.
+    fn prepare_run_dir(run_dir: &Path, file_name: &str, input: &str) -> Result<(), Option<String>> {
+        if run_dir.exists() {
+            if let Err(err) = fs::remove_dir_all(run_dir) {
+                return Err(Some(format!("Cannot clean {}: {}", run_dir.display(), err)));
             }
         }
-        self.config["__unsafe_updating"] = JsonValue::from(false);
+        if let Err(err) = fs::create_dir_all(run_dir) {
+            return Err(Some(format!("Cannot create {}: {}", run_dir.display(), err)));
+        }
+        let in_path = run_dir.join(format!("{file_name}.in"));
+        if let Err(err) = fs::write(&in_path, input) {
+            return Err(Some(format!("Cannot write {}: {}", in_path.display(), err)));
+        }
+        Ok(())
     }
 
-    /// Test the given target.
-    pub fn test(&self, name: &str, sample_group: &mut Samples) -> Result<(), Option<String>> {
-        // Get the real name.
-        let real_name = if name.ends_with(".cpp") && name.ends_with(".cc") && name.ends_with(".cxx")
-        {
-            String::from(name)
-        } else {
-            String::from(name) + "." + self.config["cc_default_extension"].to_string().as_str()
-        };
-
-        // Generate the executable's name.
-        let executable_name = real_name.split('.').collect::<Vec<&str>>()[0];
+    /// Write the input of a sample into the temporary file, and open it for the standard input of the program.
+    fn write_temp_input(temp_in: &Path, input: &str) -> std::io::Result<File> {
+        let mut in_file = OpenOptions::new().write(true).truncate(true).create(true).open(temp_in)?;
+        write!(in_file, "{}", input)?;
+        if crate::is_debug() {
+            println!("[DEBUG] Write {} to {}.", input, temp_in.display());
+        }
+        File::open(temp_in)
+    }
 
-        // Check if the old build target is already built and haven't been removed yet.
-        if Path::new(executable_name).exists() {
-            match fs::remove_file(Path::new(executable_name)) {
-                Ok(_) => {}
-                Err(err) => {
-                    return Err(Some(format!(
-                        "failed to clean the old built target:{}",
-                        err
-                    )));
-                }
+    /// Wait for a program with a timeout. Returns if it finished in time, how long it ran, and its peak memory in KB.
+    /// The memory is only measured on Linux, by polling `/proc`, so it may miss a very short peak.
+    fn wait_measured(child: &mut Child, timeout: Duration) -> (bool, Duration, Option<u64>) {
+        let start = Instant::now();
+        let status_path = format!("/proc/{}/status", child.id());
+        let mut memory_kb = None;
+        loop {
+            // `VmHWM` is the peak resident set size, like `VmHWM:     3456 kB`.
+            let peak = fs::read_to_string(&status_path).ok().and_then(|status| {
+                let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+                line.split_whitespace().nth(1)?.parse::<u64>().ok()
+            });
+            if peak.is_some() {
+                memory_kb = peak.max(memory_kb);
+            }
+            let left = timeout.saturating_sub(start.elapsed());
+            match child.wait_timeout(left.min(MEMORY_POLL_INTERVAL)) {
+                Ok(Some(_)) => return (true, start.elapsed(), memory_kb),
+                Ok(None) if !left.is_zero() => {}
+                _ => return (false, start.elapsed(), memory_kb),
             }
         }
+    }
 
+    /// Test the given target. Returns the results of the samples.
+    pub fn test(&self, name: &str, sample_group: &mut Samples, profile: Option<&str>) -> Result<Vec<SampleResult>, Option<String>> {
         // Compile the target.
-        self.compile_cpp(&real_name, executable_name, false)?;
+        let (run, directives, language) = self.compile(&self.source_name(name), false, profile)?;
+        if language.time_multiplier != 1.0 {
+            eprintln!("The time limits are multiplied by {} for {}. ", language.time_multiplier, language.name);
+        }
+
+        // Problems using file IO are run in their own directory, where the input file is put.
+        let file_io = sample_group.file_io();
+        let run_dir = self.run_dir(Path::new(&self.source_name(name)));
+        if let Some(file_name) = &file_io {
+            eprintln!("The tests read {}.in and write {}.out. ", file_name, file_name);
+        }
 
         // Run the tests
         let mut total_points = 0_u32;
-        let mut group_id = 0;
+        let mut results = Vec::new();
         let temp_in = Path::new("tkejhowiuyoiuwoiub_in.bakabaka.in.txt");
         // Iterates over each test cases
-        for sample in sample_group {
+        for (group_id, sample) in sample_group.enumerate() {
             let i = sample?;
             eprintln!("Testing test #{group_id}...");
-            let timeout = Duration::from_millis(i.timeout as u64);
+            // The timeout in the source overrides the one of the sample.
+            let timeout = directives.timeout.unwrap_or(i.timeout) as f64 * language.time_multiplier;
+            let timeout = Duration::from_millis(timeout as u64);
             let points = i.points;
 
-            let mut in_file = match OpenOptions::new()
-                .write(true)
-                .read(true)
-                .truncate(true)
-                .create(true)
-                .open(temp_in)
-            {
-                Ok(file) => file,
-                Err(err) => {
-                    return Err(Some(format!(
-                        "Error running sample group #{}: {err}",
-                        group_id
-                    )));
-                }
-            };
-            match write!(in_file, "{}", i.expected_in) {
-                Ok(_) => {}
-                Err(err) => {
-                    return Err(Some(format!(
-                        "Error running sample group #{}: {err}",
-                        group_id
-                    )));
+            // Spawn the child process.
+            let mut command = directives.command(&run);
+            match &file_io {
+                Some(file_name) => {
+                    Self::prepare_run_dir(&run_dir, file_name, &i.expected_in)?;
+                    command.current_dir(&run_dir).stdin(Stdio::null()).stdout(Stdio::null());
                 }
-            }
-            if crate::is_debug() {
-                println!(
-                    "[DEBUG] Write {} to {}.",
-                    i.expected_in,
-                    temp_in.to_str().unwrap()
-                );
-            }
-
-            in_file = match File::open(temp_in) {
-                Ok(file) => file,
-                Err(err) => {
-                    return Err(Some(format!(
-                        "Error running sample group #{}: {err}",
-                        group_id
-                    )));
+                None => {
+                    let in_file = Self::write_temp_input(temp_in, &i.expected_in)
+                        .map_err(|err| Some(format!("Error running sample group #{}: {err}", group_id)))?;
+                    command.stdin(in_file).stdout(Stdio::piped());
                 }
             };
-
-            // Spawn the child process.
-            let mut child = match Command::new(format!("./{}", executable_name))
-                .stdin(in_file)
-                .stdout(Stdio::piped())
-                .spawn()
-            {
+            let mut child = match command.spawn() {
                 Ok(c) => c,
                 Err(err) => {
                     return Err(Some(format!(
@@ -653,71 +1020,90 @@ impl Workspace {
                     )));
                 }
             };
-            match child.wait_timeout(timeout) {
-                Ok(is_timeout) => {
-                    match is_timeout {
-                        // Didn't time out.
-                        Some(_) => {
-                            // Read the result output.
-                            let mut _tmp0 = child.wait_with_output().unwrap();
-                            let content = String::from_utf8_lossy(&_tmp0.stdout[..]);
-
-                            // Check and compare the results.
-                            if content.trim() == i.expected_out {
-                                eprintln!(
-                                    "{}",
-                                    format!("Test #{group_id} passed: AC({})", i.points).green()
-                                );
-                                total_points += points;
-                            } else {
-                                let colored_diffs =
-                                    utils::strdiff::colored_diff(&i.expected_out, content.trim());
-                                eprintln!("{}", format!("Test #{group_id} failed: WA(0)").red());
-                                eprintln!("");
-                                eprintln!("Expected: ");
-                                // eprintln!("{}", i.expected_out.on_black());
-                                for i in colored_diffs.0 {
-                                    eprint!("{}", i);
-                                }
-                                eprintln!();
-                                eprintln!("Actually: ");
-                                // eprintln!("{}", content.trim().on_red());
-                                for i in colored_diffs.1 {
-                                    eprint!("{}", i);
-                                }
-                                eprintln!();
-                                eprintln!("================================================");
-                                eprintln!("Sample in: ");
-                                eprintln!("{}", i.expected_in);
-                            }
-                        }
+            // Read the output while the program runs, or it blocks once the pipe is full.
+            let stdout_reader = child.stdout.take().map(|mut stdout| {
+                thread::spawn(move || {
+                    let mut output = Vec::new();
+                    let _ = stdout.read_to_end(&mut output);
+                    output
+                })
+            });
+            let (finished, elapsed, memory_kb) = Self::wait_measured(&mut child, timeout);
+            let time_ms = elapsed.as_millis() as u64;
+            let verdict = if finished {
+                // Read the result output.
+                let content = match &file_io {
+                    Some(file_name) => fs::read_to_string(run_dir.join(format!("{file_name}.out"))).ok(),
+                    None => {
+                        let output = stdout_reader.and_then(|reader| reader.join().ok()).unwrap_or_default();
+                        Some(String::from_utf8_lossy(&output).to_string())
+                    }
+                };
 
-                        // Timeout.
-                        None => {
-                            child.kill().unwrap();
-                            eprintln!("{}", format!("Test #{group_id} failed: TLE(0)").red());
+                // Check and compare the results.
+                match content {
+                    None => {
+                        let file_name = file_io.as_deref().unwrap_or_default();
+                        eprintln!("{}", format!("Test #{group_id} failed: MOF(0), {file_name}.out is missing").red());
+                        Verdict::MissingOutputFile
+                    }
+                    Some(content) if content.trim() == i.expected_out => {
+                        let memory = memory_kb.map(|kb| format!(", {:.1}MB", kb as f64 / 1024.0)).unwrap_or_default();
+                        eprintln!(
+                            "{}",
+                            format!("Test #{group_id} passed: AC({}) in {}ms{}", i.points, time_ms, memory).green()
+                        );
+                        total_points += points;
+                        Verdict::Accepted
+                    }
+                    Some(content) => {
+                        let colored_diffs =
+                            utils::strdiff::colored_diff(&i.expected_out, content.trim());
+                        eprintln!("{}", format!("Test #{group_id} failed: WA(0)").red());
+                        eprintln!();
+                        eprintln!("Expected: ");
+                        // eprintln!("{}", i.expected_out.on_black());
+                        for i in colored_diffs.0 {
+                            eprint!("{}", i);
+                        }
+                        eprintln!();
+                        eprintln!("Actually: ");
+                        // eprintln!("{}", content.trim().on_red());
+                        for i in colored_diffs.1 {
+                            eprint!("{}", i);
                         }
+                        eprintln!();
+                        eprintln!("================================================");
+                        eprintln!("Sample in: ");
+                        eprintln!("{}", i.expected_in);
+                        Verdict::WrongAnswer
                     }
                 }
-                Err(_) => {
-                    child.kill().unwrap();
-                    eprintln!("{}", format!("Test #{group_id} failed: TLE(0)").red());
-                }
-            }
-            group_id += 1;
+            } else {
+                // Killing the program also closes its output, which stops the reader thread.
+                child.kill().unwrap();
+                let _ = child.wait();
+                eprintln!("{}", format!("Test #{group_id} failed: TLE(0)").red());
+                Verdict::TimeLimitExceeded
+            };
+            results.push(SampleResult {
+                verdict,
+                time_ms,
+                memory_kb,
+                points: if verdict == Verdict::Accepted { points } else { 0 },
+            });
         }
 
         println!("Total points you get: {}", total_points);
 
-        // Finally remove the files.
-        if let Err(err) = fs::remove_file(Path::new(&format!("./{}", executable_name))) {
-            return Err(Some(format!("Failed to remove built target: {err}")));
-        }
-        if let Err(err) = fs::remove_file(temp_in) {
-            return Err(Some(format!(
-                "Failed to remove temporary input file: {err}"
-            )));
+        // Finally remove the temporary file. The executable is kept in the build directory.
+        if file_io.is_none() {
+            if let Err(err) = fs::remove_file(temp_in) {
+                return Err(Some(format!(
+                    "Failed to remove temporary input file: {err}"
+                )));
+            }
         }
-        Ok(())
+        Ok(results)
     }
 }